@@ -0,0 +1,253 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Take};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use tar::{Archive, EntryType};
+
+use crate::install::check_paket_path;
+use crate::scripts::PaketScripts;
+use crate::sha256;
+use crate::toml_structs::paket_toml::{self, Config, ScriptPhase};
+use crate::{PaketError, Result};
+
+/// Type of an entry in `data.tar.gz`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EntryKind {
+    File,
+    Folder,
+    Symlink,
+}
+
+/// A file, folder or symlink in `data.tar.gz` of a .paket file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PayloadEntry {
+    /// Relative path in `data.tar.gz` like `usr/bin/helloworld` or `$USER_HOME/.fonts/hack`.
+    pub path: PathBuf,
+
+    pub kind: EntryKind,
+
+    /// Permission bits like `0o755`.
+    pub mode: u32,
+
+    /// Target of the symlink.
+    pub link_name: Option<PathBuf>,
+
+    /// Content of the file. Empty for folders and symlinks.
+    pub content: Vec<u8>,
+}
+
+/// Reader of a .paket file.
+///
+/// A .paket file is a tar archive with:
+/// - `Paket.toml`: Configuration of the paket. See: `paket_toml::Config`
+/// - `SHA256SUM`: SHA256 of `data.tar.gz`
+/// - `data.tar.gz`: Files to install with their paths in the installed system.
+/// - `scripts/<phase>`: Maintainer scripts. (Optional) See: `paket_toml::Scripts`
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::archive::PaketArchive;
+///
+/// let mut archive = PaketArchive::open("helloworld_0.1.0.paket").unwrap();
+/// archive.verify_checksum().unwrap();
+/// println!("{} {}", archive.get_config().package.name, archive.get_config().package.version);
+///
+/// for entry in archive.payload().unwrap().entries().unwrap() {
+///     let entry = entry.unwrap();
+///     println!("{} {:o}", entry.path.display(), entry.mode);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PaketArchive<R: Read + Seek> {
+    reader: R,
+    config: Config,
+    paket_toml: String,
+    sha256sum: String,
+    scripts: PaketScripts,
+
+    /// Position and size of `data.tar.gz` in the reader.
+    data_offset: u64,
+    data_size: u64,
+}
+
+impl PaketArchive<File> {
+    /// Open a .paket file.
+    pub fn open(paket_path: impl AsRef<Path>) -> Result<PaketArchive<File>> {
+        PaketArchive::from_reader(File::open(paket_path)?)
+    }
+}
+
+impl<R: Read + Seek> PaketArchive<R> {
+    /// Read a .paket file from a reader and check its layout.
+    pub fn from_reader(mut reader: R) -> Result<PaketArchive<R>> {
+        // The reader may not be at the start
+        let start = reader.stream_position()?;
+
+        let mut paket_toml = None;
+        let mut sha256sum = None;
+        let mut data = None;
+        let mut scripts = Vec::new();
+
+        {
+            let mut ar = Archive::new(&mut reader);
+            for entry in ar.entries()? {
+                let mut entry = entry?;
+                let entry_path = entry.path()?.to_string_lossy().to_string();
+
+                match entry_path.as_str() {
+                    "Paket.toml" => {
+                        let mut content = String::new();
+                        entry.read_to_string(&mut content)?;
+                        paket_toml = Some(content);
+                    }
+                    "SHA256SUM" => {
+                        let mut content = String::new();
+                        entry.read_to_string(&mut content)?;
+                        sha256sum = Some(content.trim().to_string());
+                    }
+                    "data.tar.gz" => data = Some((start + entry.raw_file_position(), entry.size())),
+                    _ => {
+                        let phase = entry_path
+                            .strip_prefix("scripts/")
+                            .and_then(ScriptPhase::from_name)
+                            .ok_or_else(|| {
+                                PaketError::InvalidPaketArchive(format!(
+                                    "Unknown file in the paket: {entry_path}"
+                                ))
+                            })?;
+
+                        let mut content = Vec::new();
+                        entry.read_to_end(&mut content)?;
+                        scripts.push((phase, content));
+                    }
+                }
+            }
+        }
+
+        let missing = |name: &str| PaketError::InvalidPaketArchive(format!("{name} is missing"));
+        let paket_toml = paket_toml.ok_or_else(|| missing("Paket.toml"))?;
+        let sha256sum = sha256sum.ok_or_else(|| missing("SHA256SUM"))?;
+        let (data_offset, data_size) = data.ok_or_else(|| missing("data.tar.gz"))?;
+
+        Ok(PaketArchive {
+            reader,
+            config: paket_toml::read_config_from_str(&paket_toml)?,
+            paket_toml,
+            sha256sum,
+            scripts: PaketScripts::new(scripts),
+            data_offset,
+            data_size,
+        })
+    }
+
+    /// Parsed `Paket.toml` of the paket.
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Content of `Paket.toml` as it is in the paket.
+    pub fn get_paket_toml(&self) -> &str {
+        &self.paket_toml
+    }
+
+    /// SHA256 of `data.tar.gz` written in `SHA256SUM`.
+    pub fn get_sha256sum(&self) -> &str {
+        &self.sha256sum
+    }
+
+    /// Maintainer scripts in `scripts/<phase>`.
+    pub fn get_scripts(&self) -> &PaketScripts {
+        &self.scripts
+    }
+
+    /// Reader of `data.tar.gz` as it is in the paket (compressed).
+    fn data_reader(&mut self) -> Result<Take<&mut R>> {
+        self.reader.seek(SeekFrom::Start(self.data_offset))?;
+
+        Ok((&mut self.reader).take(self.data_size))
+    }
+
+    /// Check if the SHA256 of `data.tar.gz` is the same as `SHA256SUM`.
+    pub fn verify_checksum(&mut self) -> Result<()> {
+        let calculated = sha256::calculate_sha256_of_reader(self.data_reader()?)?;
+        if calculated != self.sha256sum {
+            return Err(PaketError::ChecksumMismatch(format!(
+                "data.tar.gz of {}: expected {}, calculated {calculated}",
+                self.config.get_paket_full_name(),
+                self.sha256sum
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Files in `data.tar.gz` to install.
+    pub fn payload(&mut self) -> Result<Payload<Take<&mut R>>> {
+        Ok(Payload {
+            archive: Archive::new(GzDecoder::new(self.data_reader()?)),
+        })
+    }
+}
+
+/// Decompressed `data.tar.gz` of a .paket file. See: `PaketArchive::payload`
+pub struct Payload<R: Read> {
+    archive: Archive<GzDecoder<R>>,
+}
+
+impl<R: Read> Payload<R> {
+    /// Iterate over the files, folders and symlinks in `data.tar.gz`. Other entry types are skipped.
+    ///
+    /// Paths are checked to be relative and not to go to the parent folders.
+    pub fn entries(&mut self) -> Result<impl Iterator<Item = Result<PayloadEntry>> + '_> {
+        Ok(self
+            .archive
+            .entries()?
+            .filter_map(|entry| read_payload_entry(entry).transpose()))
+    }
+
+    /// Extract the files, folders and symlinks into a folder as they are in `data.tar.gz`.
+    pub fn unpack_in(&mut self, folder: &Path) -> Result<()> {
+        for entry in self.archive.entries()? {
+            let mut entry = entry?;
+            check_paket_path(&entry.path()?)?;
+
+            if matches!(
+                entry.header().entry_type(),
+                EntryType::Regular | EntryType::Symlink | EntryType::Directory
+            ) {
+                entry.unpack_in(folder)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn read_payload_entry<R: Read>(
+    entry: std::io::Result<tar::Entry<'_, R>>,
+) -> Result<Option<PayloadEntry>> {
+    let mut entry = entry?;
+    let path = entry.path()?.to_path_buf();
+    check_paket_path(&path)?;
+
+    let kind = match entry.header().entry_type() {
+        EntryType::Regular => EntryKind::File,
+        EntryType::Directory => EntryKind::Folder,
+        EntryType::Symlink => EntryKind::Symlink,
+        _ => return Ok(None),
+    };
+
+    let mut content = Vec::new();
+    if kind == EntryKind::File {
+        entry.read_to_end(&mut content)?;
+    }
+
+    Ok(Some(PayloadEntry {
+        path,
+        kind,
+        mode: entry.header().mode()? & 0o7777,
+        link_name: entry.link_name()?.map(|l| l.to_path_buf()),
+        content,
+    }))
+}
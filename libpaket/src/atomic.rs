@@ -0,0 +1,21 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+/// Write the content into a temporary file next to `path`. It replaces `path` when it's renamed. See: `write_replacing`
+pub(crate) fn write_temporary(path: &Path, content: &[u8]) -> Result<PathBuf> {
+    let mut temporary_path = path.as_os_str().to_owned();
+    temporary_path.push(".part");
+
+    fs::write(&temporary_path, content)?;
+
+    Ok(PathBuf::from(temporary_path))
+}
+
+/// Write a file with a temporary file first, not to leave a broken file if the write fails.
+pub(crate) fn write_replacing(path: &Path, content: &[u8]) -> Result<()> {
+    fs::rename(write_temporary(path, content)?, path)?;
+
+    Ok(())
+}
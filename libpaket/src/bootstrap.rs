@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use crate::archive::PaketArchive;
+use crate::dependency;
+use crate::install::{InstallOptions, Installer};
+use crate::placeholder::UserHomePolicy;
+use crate::{PaketError, Result};
+
+/// Folders of a minimal root filesystem.
+const ROOT_FOLDERS: &[&str] = &[
+    "etc/skel",
+    "home",
+    "root",
+    "tmp",
+    "usr/bin",
+    "usr/lib",
+    "usr/share",
+    "var/tmp",
+];
+
+/// Create a minimal root filesystem in `root_dir` from .paket files.
+///
+/// Bootstrap steps:
+/// 1. Check `root_dir` is empty or doesn't exist.
+/// 2. Check the dependencies of the pakets. Every dependency must be in the given pakets.
+/// 3. Create the base folders of the root filesystem and initialize the paket database in `<root_dir>/var/lib/paket`.
+/// 4. Install the pakets after their dependencies.
+///
+/// If a step fails, `root_dir` is left as it was found.
+///
+/// `$USER_HOME` files of configuration pakets are installed into `/etc/skel` because there are no users yet.
+///
+/// Example:
+/// ```rust,no_run
+/// use std::path::{Path, PathBuf};
+///
+/// let installer = libpaket::bootstrap::bootstrap(
+///     Path::new("./rootfs"),
+///     &[PathBuf::from("helloworld_0.1.0.paket")],
+/// )
+/// .unwrap();
+/// ```
+pub fn bootstrap(root_dir: &Path, paket_path_list: &[PathBuf]) -> Result<Installer> {
+    if root_dir.exists() && fs::read_dir(root_dir)?.next().is_some() {
+        return Err(PaketError::FolderNotEmpty(
+            root_dir.to_string_lossy().to_string(),
+        ));
+    }
+
+    let configs = paket_path_list
+        .iter()
+        .map(|p| Ok(PaketArchive::open(p)?.get_config().clone()))
+        .collect::<Result<Vec<_>>>()?;
+    dependency::resolve_install_order(&configs.iter().collect::<Vec<_>>(), &HashMap::new())?;
+
+    let root_existed = root_dir.exists();
+    let result = create_root_filesystem(root_dir, paket_path_list);
+
+    if result.is_err() {
+        let _ = match root_existed {
+            true => remove_folder_contents(root_dir),
+            false => fs::remove_dir_all(root_dir).map_err(PaketError::from),
+        };
+    }
+
+    result
+}
+
+fn create_root_filesystem(root_dir: &Path, paket_path_list: &[PathBuf]) -> Result<Installer> {
+    for folder in ROOT_FOLDERS {
+        fs::create_dir_all(root_dir.join(folder))?;
+    }
+    fs::set_permissions(root_dir.join("tmp"), fs::Permissions::from_mode(0o1777))?;
+    fs::set_permissions(root_dir.join("var/tmp"), fs::Permissions::from_mode(0o1777))?;
+    fs::set_permissions(root_dir.join("root"), fs::Permissions::from_mode(0o700))?;
+
+    let installer = Installer::new(root_dir).with_options(InstallOptions {
+        home_policy: UserHomePolicy::Skel,
+        ..Default::default()
+    });
+    installer.init_database()?;
+
+    installer.install_paket_files(paket_path_list)?;
+
+    Ok(installer)
+}
+
+fn remove_folder_contents(folder: &Path) -> Result<()> {
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        match path.is_dir() && !path.is_symlink() {
+            true => fs::remove_dir_all(path)?,
+            false => fs::remove_file(path)?,
+        }
+    }
+
+    Ok(())
+}
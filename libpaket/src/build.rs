@@ -1,12 +1,13 @@
 use std::fs::{DirEntry, File};
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use flate2::write::GzEncoder;
 use flate2::Compression;
 
+use crate::placeholder::Placeholder;
 use crate::sha256;
-use crate::toml_structs::paket_toml::{self, PackageType};
+use crate::toml_structs::paket_toml::{self, PackageType, ScriptPhase};
 use crate::PaketError;
 use crate::Result;
 
@@ -23,6 +24,7 @@ fn list_files(folder_path: &Path) -> io::Result<Vec<DirEntry>> {
     Ok(std::fs::read_dir(folder_path)?
         .filter_map(|r| r.ok()) // Get rid of Err variants for Result<DirEntry>
         .filter(|r| r.path().is_file()) // Filter out non-folders
+        .filter(|r| r.file_name() != "Paket.toml") // Paket.toml is added to the .paket, not to data.tar.gz
         .collect())
 }
 
@@ -102,7 +104,7 @@ fn create_data_tar_gz(
                 // `assets_folder = "assets"` property check
                 if Some(d.file_name().to_string_lossy().to_string()) == application.assets_folder {
                     tar_builder.append_dir_all(
-                        format!("/usr/share/{}/assets", paket_config.package.name),
+                        format!("usr/share/{}/assets", paket_config.package.name),
                         d.path(),
                     )?;
                 } else {
@@ -119,7 +121,7 @@ fn create_data_tar_gz(
                 if filename == application.executable.as_str() {
                     tar_builder.append_path_with_name(
                         f.path(),
-                        Path::new("/usr/bin/").join(f.file_name()),
+                        Path::new("usr/bin/").join(f.file_name()),
                     )?
                 } else if filename
                     == application
@@ -131,7 +133,7 @@ fn create_data_tar_gz(
                     // Add `desktop_file` if exists
                     tar_builder.append_path_with_name(
                         f.path(),
-                        Path::new("/usr/share/applications/").join(f.file_name()),
+                        Path::new("usr/share/applications/").join(f.file_name()),
                     )?;
                 } else if filename
                     == application
@@ -148,34 +150,34 @@ fn create_data_tar_gz(
                     // Add `icon`
                     tar_builder.append_path_with_name(
                         f.path(),
-                        Path::new("/usr/share/icons/hicolor/scalable/apps").join(f.file_name()),
+                        Path::new("usr/share/icons/hicolor/scalable/apps").join(f.file_name()),
                     )?;
                 } else {
                     tar_builder.append_path_with_name(f.path(), f.file_name())?;
                 }
+            }
 
-                // Generate desktop file if doesn't exist
-                if application.desktop_file.is_none() {
-                    let desktop_file_content = generate_desktop_file_content(paket_config);
-
-                    let mut header = tar::Header::new_gnu();
-                    header.set_size(desktop_file_content.len() as u64);
-                    header.set_entry_type(tar::EntryType::Regular);
-                    header.set_path(format!(
-                        "/usr/share/applications/{}.desktop",
-                        paket_config.package.name
-                    ))?;
-                    header.set_mtime(
-                        std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_secs(),
-                    );
-                    header.set_mode(0o755);
-                    header.set_cksum();
-
-                    tar_builder.append(&header, desktop_file_content.as_bytes())?;
-                }
+            // Generate desktop file if doesn't exist
+            if application.desktop_file.is_none() {
+                let desktop_file_content = generate_desktop_file_content(paket_config);
+
+                let mut header = tar::Header::new_gnu();
+                header.set_size(desktop_file_content.len() as u64);
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_path(format!(
+                    "usr/share/applications/{}.desktop",
+                    paket_config.package.name
+                ))?;
+                header.set_mtime(
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap()
+                        .as_secs(),
+                );
+                header.set_mode(0o755);
+                header.set_cksum();
+
+                tar_builder.append(&header, desktop_file_content.as_bytes())?;
             }
         }
 
@@ -188,13 +190,13 @@ fn create_data_tar_gz(
                 // `assets_folder = "assets"` property check
                 if d.file_name().to_str().unwrap() == script.sources_folder.as_str() {
                     tar_builder.append_dir_all(
-                        format!("/usr/share/{}/src", paket_config.package.name),
+                        format!("usr/share/{}/src", paket_config.package.name),
                         d.path(),
                     )?;
                 } else if Some(d.file_name().to_string_lossy().to_string()) == script.assets_folder
                 {
                     tar_builder.append_dir_all(
-                        format!("/usr/share/{}/assets", paket_config.package.name),
+                        format!("usr/share/{}/assets", paket_config.package.name),
                         d.path(),
                     )?;
                 } else {
@@ -211,7 +213,7 @@ fn create_data_tar_gz(
                 if filename == script.executable.as_str() {
                     tar_builder.append_path_with_name(
                         f.path(),
-                        Path::new("/usr/bin/").join(f.file_name()),
+                        Path::new("usr/bin/").join(f.file_name()),
                     )?
                 } else if filename
                     == script
@@ -223,7 +225,7 @@ fn create_data_tar_gz(
                     // Add `desktop_file` if exists
                     tar_builder.append_path_with_name(
                         f.path(),
-                        Path::new("/usr/share/applications/").join(f.file_name()),
+                        Path::new("usr/share/applications/").join(f.file_name()),
                     )?;
                 } else if filename == script.icon.as_ref().unwrap_or(&"".to_string()).as_str() {
                     if !script.icon.as_ref().unwrap().ends_with(".svg") {
@@ -234,7 +236,7 @@ fn create_data_tar_gz(
                     // Add `icon`
                     tar_builder.append_path_with_name(
                         f.path(),
-                        Path::new("/usr/share/icons/hicolor/scalable/apps").join(f.file_name()),
+                        Path::new("usr/share/icons/hicolor/scalable/apps").join(f.file_name()),
                     )?;
                 } else {
                     tar_builder.append_path_with_name(f.path(), f.file_name())?;
@@ -244,6 +246,14 @@ fn create_data_tar_gz(
 
         PackageType::Configuration => {
             for p in folders_list {
+                // Top level folders starting with `$` must be a known placeholder like `$USER_HOME`
+                let folder_name = p.file_name().to_string_lossy().to_string();
+                if folder_name.starts_with('$') && Placeholder::from_name(&folder_name).is_none() {
+                    return Err(PaketError::InvalidPaketPath(format!(
+                        "Unknown placeholder folder: {folder_name}"
+                    )));
+                }
+
                 tar_builder.append_dir_all(p.file_name(), p.path())?;
             }
         }
@@ -290,18 +300,19 @@ fn add_sha256sum_of_data_to_tar(
     let mut sha256sum = sha256::calculate_sha256(compressed_data);
     sha256sum.push('\n');
 
-    append_bytes_to_tar(tar_builder, compressed_data, "SHA256SUM")?;
+    append_bytes_to_tar(tar_builder, sha256sum.as_bytes(), "SHA256SUM")?;
 
     Ok(())
 }
 
 /// Create a .paket file.
 fn create_paket_archive(
-    archive_name: &str,
+    archive_path: &Path,
     toml_file_path: &Path,
     compressed_data: Vec<u8>,
+    scripts: &[(ScriptPhase, PathBuf)],
 ) -> io::Result<File> {
-    let file = File::create(archive_name)?;
+    let file = File::create(archive_path)?;
     let mut tar_builder = tar::Builder::new(file);
 
     // Add Paket.toml to tar
@@ -313,11 +324,22 @@ fn create_paket_archive(
     // Add data.tar.gz
     append_bytes_to_tar(&mut tar_builder, &compressed_data, "data.tar.gz")?;
 
+    // Add maintainer scripts as `scripts/<phase>`
+    for (phase, script_path) in scripts {
+        append_bytes_to_tar(
+            &mut tar_builder,
+            &std::fs::read(script_path)?,
+            format!("scripts/{}", phase.name()),
+        )?;
+    }
+
     // Create .paket file
     tar_builder.into_inner()
 }
 
-/// Create a .paket file from a Paket.toml config file path.
+/// Create a .paket file from a Paket.toml config file path into the `output_folder`.
+///
+/// Returns the path of the created .paket file.
 ///
 /// Example:
 /// ```rust,no_run
@@ -328,7 +350,7 @@ fn create_paket_archive(
 /// // └── usr/
 /// //     └── bin/
 /// //         └── myapp
-/// libpaket::build::create_paket_from_toml(Path::new("./")).unwrap();
+/// libpaket::build::create_paket_from_toml(Path::new("./"), Path::new("./")).unwrap();
 ///
 /// // Then there should be a paket file created if everything is ok:
 /// // Files:
@@ -339,14 +361,35 @@ fn create_paket_archive(
 /// //     └── bin/
 /// //         └── myapp
 /// ```
-pub fn create_paket_from_toml(toml_folder_path: &Path) -> Result<(String, File)> {
+pub fn create_paket_from_toml(
+    toml_folder_path: &Path,
+    output_folder: &Path,
+) -> Result<(PathBuf, File)> {
     // Read Config struct from toml file
     let toml_file_path = toml_folder_path.join("Paket.toml");
     let paket_config = paket_toml::read_config_from_toml(&toml_file_path)?;
     let archive_name = paket_config.get_paket_archive_name();
 
+    // Maintainer scripts are added to the .paket, not to data.tar.gz
+    let mut scripts = Vec::new();
+    if let Some(s) = &paket_config.scripts {
+        for phase in ScriptPhase::all() {
+            if let Some(script_file) = s.get(*phase) {
+                let script_path = toml_folder_path.join(script_file);
+                if !script_path.is_file() {
+                    return Err(PaketError::FileNotFound(
+                        script_path.to_string_lossy().to_string(),
+                    ));
+                }
+
+                scripts.push((*phase, script_path));
+            }
+        }
+    }
+
     let paket_folder_dir_list = list_directories(toml_folder_path)?;
-    let paket_folder_file_list = list_files(toml_folder_path)?;
+    let mut paket_folder_file_list = list_files(toml_folder_path)?;
+    paket_folder_file_list.retain(|f| !scripts.iter().any(|(_, s)| *s == f.path()));
     println!("Dir List:{:#?}", paket_folder_dir_list);
     println!("File List:{:#?}", paket_folder_file_list);
 
@@ -358,9 +401,11 @@ pub fn create_paket_from_toml(toml_folder_path: &Path) -> Result<(String, File)>
     )?;
 
     // Create app_1.0.0.paket
-    let paket_file = create_paket_archive(&archive_name, &toml_file_path, compressed_data)?;
+    let archive_path = output_folder.join(archive_name);
+    let paket_file =
+        create_paket_archive(&archive_path, &toml_file_path, compressed_data, &scripts)?;
 
     paket_file.sync_all()?;
 
-    Ok((archive_name, paket_file))
+    Ok((archive_path, paket_file))
 }
@@ -0,0 +1,249 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use semver::Version;
+
+use crate::toml_structs::index_toml::IndexEntry;
+use crate::{PaketError, Result};
+
+/// A downloaded .paket file in the cache folder.
+///
+/// Files are stored at `<cache_dir>/<name>/<version>/<sha256>/<file name>`, so every build of every version is kept
+/// until the cache is cleaned. See: `clean_cache`
+#[derive(Debug, PartialEq, Clone)]
+pub struct CachedPaket {
+    pub name: String,
+    pub version: Version,
+    pub sha256: String,
+    pub path: PathBuf,
+
+    /// Size of the .paket file in bytes.
+    pub size: u64,
+
+    /// Last modification time of the .paket file, the time it was downloaded.
+    pub modified: Option<SystemTime>,
+}
+
+/// Which cached .paket files `clean_cache` removes. All of them are removed with the default options.
+#[derive(Debug, Default, Clone)]
+pub struct CleanOptions {
+    /// Keep the newest N versions of every paket.
+    pub keep_versions: Option<usize>,
+
+    /// Remove the oldest downloads until the cache is smaller than this size in bytes.
+    pub max_size: Option<u64>,
+}
+
+/// Path of a paket of the repository index in the cache folder.
+pub fn paket_path(cache_dir: &Path, entry: &IndexEntry) -> Result<PathBuf> {
+    let file_name = Path::new(&entry.filename).file_name().ok_or_else(|| {
+        PaketError::DownloadError(format!("Invalid filename: {}", entry.filename))
+    })?;
+
+    // Names and versions are used as folder names, don't let them escape the cache folder
+    for part in [&entry.name, &entry.version, &entry.sha256] {
+        if part.is_empty() || part.contains('/') || part.starts_with('.') {
+            return Err(PaketError::DownloadError(format!(
+                "Invalid paket in the index: {} {}",
+                entry.name, entry.version
+            )));
+        }
+    }
+
+    Ok(cache_dir
+        .join(&entry.name)
+        .join(&entry.version)
+        .join(&entry.sha256)
+        .join(file_name))
+}
+
+/// List the .paket files in the cache folder, ordered by their names and versions.
+///
+/// Example:
+/// ```rust,no_run
+/// use std::path::Path;
+/// use libpaket::cache::list_cache;
+///
+/// for paket in list_cache(Path::new("/var/lib/paket/cache")).unwrap() {
+///     println!("{} {} ({} bytes)", paket.name, paket.version, paket.size);
+/// }
+/// ```
+pub fn list_cache(cache_dir: &Path) -> Result<Vec<CachedPaket>> {
+    let mut pakets = Vec::new();
+    if !cache_dir.is_dir() {
+        return Ok(pakets);
+    }
+
+    for name_folder in sub_folders(cache_dir)? {
+        for version_folder in sub_folders(&name_folder)? {
+            let Some(version) = file_name(&version_folder).and_then(|v| Version::parse(&v).ok())
+            else {
+                continue;
+            };
+
+            for hash_folder in sub_folders(&version_folder)? {
+                for entry in fs::read_dir(&hash_folder)? {
+                    let path = entry?.path();
+                    if path.extension().is_none_or(|e| e != "paket") {
+                        continue;
+                    }
+
+                    let metadata = fs::metadata(&path)?;
+                    pakets.push(CachedPaket {
+                        name: file_name(&name_folder).unwrap_or_default(),
+                        version: version.clone(),
+                        sha256: file_name(&hash_folder).unwrap_or_default(),
+                        path,
+                        size: metadata.len(),
+                        modified: metadata.modified().ok(),
+                    });
+                }
+            }
+        }
+    }
+
+    pakets.sort_by(order);
+    Ok(pakets)
+}
+
+/// Order of the cached .paket files: names, versions, then SHA256s.
+fn order(a: &CachedPaket, b: &CachedPaket) -> Ordering {
+    (&a.name, &a.version, &a.sha256).cmp(&(&b.name, &b.version, &b.sha256))
+}
+
+/// Remove the cached .paket files by the options. Returns the removed files.
+///
+/// 1. Without options, everything in the cache folder is removed.
+/// 2. `keep_versions` removes the older versions of every paket.
+/// 3. `max_size` removes the oldest downloads of the remaining files until the cache fits.
+pub fn clean_cache(cache_dir: &Path, options: &CleanOptions) -> Result<Vec<CachedPaket>> {
+    let cached = list_cache(cache_dir)?;
+
+    if options.keep_versions.is_none() && options.max_size.is_none() {
+        if cache_dir.is_dir() {
+            for entry in fs::read_dir(cache_dir)? {
+                let path = entry?.path();
+                match path.is_dir() {
+                    true => fs::remove_dir_all(path)?,
+                    false => fs::remove_file(path)?,
+                }
+            }
+        }
+
+        return Ok(cached);
+    }
+
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+
+    match options.keep_versions {
+        Some(keep_versions) => {
+            let mut by_name: BTreeMap<&str, Vec<&CachedPaket>> = BTreeMap::new();
+            for paket in &cached {
+                by_name.entry(&paket.name).or_default().push(paket);
+            }
+
+            for mut pakets in by_name.into_values() {
+                pakets.sort_by_key(|p| Reverse((&p.version, p.modified)));
+
+                let mut versions: Vec<&Version> = Vec::new();
+                for paket in pakets {
+                    if !versions.contains(&&paket.version) {
+                        versions.push(&paket.version);
+                    }
+
+                    match versions.len() <= keep_versions {
+                        true => kept.push(paket),
+                        false => removed.push(paket.clone()),
+                    }
+                }
+            }
+        }
+        None => kept.extend(&cached),
+    }
+
+    if let Some(max_size) = options.max_size {
+        kept.sort_by_key(|p| Reverse(p.modified));
+
+        let mut total_size = 0;
+        for paket in kept {
+            total_size += paket.size;
+            if total_size > max_size {
+                removed.push(paket.clone());
+            }
+        }
+    }
+
+    for paket in &removed {
+        remove_cached_paket(cache_dir, paket)?;
+    }
+
+    removed.sort_by(order);
+    Ok(removed)
+}
+
+/// Remove a cached .paket file with its empty parent folders.
+fn remove_cached_paket(cache_dir: &Path, paket: &CachedPaket) -> Result<()> {
+    fs::remove_file(&paket.path)?;
+
+    let mut folder = paket.path.parent();
+    while let Some(f) = folder.filter(|f| *f != cache_dir) {
+        if fs::read_dir(f)?.next().is_some() {
+            break;
+        }
+
+        fs::remove_dir(f)?;
+        folder = f.parent();
+    }
+
+    Ok(())
+}
+
+fn sub_folders(folder: &Path) -> Result<Vec<PathBuf>> {
+    let mut folders = Vec::new();
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            folders.push(path);
+        }
+    }
+
+    Ok(folders)
+}
+
+fn file_name(path: &Path) -> Option<String> {
+    Some(path.file_name()?.to_string_lossy().to_string())
+}
+
+/// Parse a size like `500M`, `2G`, `1.5GiB` or `1024` (bytes). Units are powers of 1024.
+///
+/// Example:
+/// ```rust
+/// use libpaket::cache::parse_size;
+///
+/// assert_eq!(Some(1536), parse_size("1.5K"));
+/// assert_eq!(Some(500 * 1024 * 1024), parse_size("500MiB"));
+/// assert_eq!(None, parse_size("big"));
+/// ```
+pub fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let unit_start = size
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(unit_start);
+
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return None,
+    };
+
+    let number: f64 = number.parse().ok()?;
+    Some((number * multiplier as f64) as u64)
+}
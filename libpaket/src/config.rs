@@ -0,0 +1,130 @@
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+use crate::cache;
+use crate::install::Installer;
+use crate::remote::CACHE_FOLDER;
+use crate::toml_structs::global_toml::{ColorMode, GlobalConfig};
+use crate::toml_structs::paket_toml;
+use crate::{PaketError, Result};
+
+/// Global configuration file. See: `global_toml::GlobalConfig`
+pub const CONFIG_FILE: &str = "/etc/paket/paket.toml";
+
+/// Environment variable to read the global configuration from another file.
+pub const CONFIG_FILE_ENV: &str = "PAKET_CONFIG";
+
+/// Read a global configuration file. The default configuration is returned if the file doesn't exist.
+pub fn read_config(config_path: &Path) -> Result<GlobalConfig> {
+    match config_path.exists() {
+        true => paket_toml::read_toml_file(config_path),
+        false => Ok(GlobalConfig::default()),
+    }
+}
+
+/// Load the global configuration from `/etc/paket/paket.toml` (or `$PAKET_CONFIG`) with the environment variables.
+///
+/// Fields are overridden by these environment variables:
+/// - `PAKET_ROOT`: `root`
+/// - `PAKET_CACHE_DIR`: `cache_dir`
+/// - `PAKET_MAX_CACHE_SIZE`: `max_cache_size`
+/// - `PAKET_PARALLEL_DOWNLOADS`: `parallel_downloads`
+/// - `PAKET_INSTALL_RECOMMENDS`: `install_recommends` (`true`, `false`, `1` or `0`)
+/// - `PAKET_COLOR`: `color` (`auto`, `always` or `never`). `NO_COLOR` disables the colors too.
+/// - `PAKET_LANGUAGE`: `language`
+///
+/// Example:
+/// ```rust,no_run
+/// let config = libpaket::config::load().unwrap();
+/// println!("{} pakets are downloaded at the same time", config.parallel_downloads);
+/// ```
+pub fn load() -> Result<GlobalConfig> {
+    let config_path = std::env::var_os(CONFIG_FILE_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE));
+
+    with_env_overrides(read_config(&config_path)?, |name| std::env::var(name).ok())
+}
+
+/// Override the fields of the configuration with the environment variables. See: `load`
+///
+/// `var` returns the value of an environment variable by its name.
+pub fn with_env_overrides(
+    mut config: GlobalConfig,
+    var: impl Fn(&str) -> Option<String>,
+) -> Result<GlobalConfig> {
+    let var = |name: &str| var(name).filter(|v| !v.is_empty());
+    let invalid = |name: &str, value: &str| {
+        PaketError::InvalidConfig(format!("{name} has an invalid value: {value}"))
+    };
+
+    if let Some(root) = var("PAKET_ROOT") {
+        config.root = PathBuf::from(root);
+    }
+
+    if let Some(cache_dir) = var("PAKET_CACHE_DIR") {
+        config.cache_dir = Some(PathBuf::from(cache_dir));
+    }
+
+    if let Some(value) = var("PAKET_MAX_CACHE_SIZE") {
+        config.max_cache_size = Some(value);
+    }
+
+    if let Some(value) = var("PAKET_PARALLEL_DOWNLOADS") {
+        config.parallel_downloads = value
+            .parse()
+            .map_err(|_| invalid("PAKET_PARALLEL_DOWNLOADS", &value))?;
+    }
+
+    if let Some(value) = var("PAKET_INSTALL_RECOMMENDS") {
+        config.install_recommends = match value.as_str() {
+            "true" | "1" => true,
+            "false" | "0" => false,
+            _ => return Err(invalid("PAKET_INSTALL_RECOMMENDS", &value)),
+        };
+    }
+
+    if var("NO_COLOR").is_some() {
+        config.color = ColorMode::Never;
+    }
+    if let Some(value) = var("PAKET_COLOR") {
+        config.color =
+            ColorMode::from_name(&value).ok_or_else(|| invalid("PAKET_COLOR", &value))?;
+    }
+
+    if let Some(language) = var("PAKET_LANGUAGE") {
+        config.language = Some(language);
+    }
+
+    Ok(config)
+}
+
+/// Folder of the downloaded .paket files: `cache_dir` of the configuration or `<state_dir>/cache` of the installer.
+pub fn cache_dir(config: &GlobalConfig, installer: &Installer) -> PathBuf {
+    match &config.cache_dir {
+        Some(cache_dir) => cache_dir.clone(),
+        None => installer.get_state_dir().join(CACHE_FOLDER),
+    }
+}
+
+/// Size limit of the cache folder in bytes, parsed from `max_cache_size` of the configuration.
+pub fn max_cache_size(config: &GlobalConfig) -> Result<Option<u64>> {
+    match &config.max_cache_size {
+        Some(size) => match cache::parse_size(size) {
+            Some(bytes) => Ok(Some(bytes)),
+            None => Err(PaketError::InvalidConfig(format!(
+                "max_cache_size has an invalid value: {size}"
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Check if the output should be colored by the `color` of the configuration.
+pub fn use_color(config: &GlobalConfig) -> bool {
+    match config.color {
+        ColorMode::Auto => std::io::stdout().is_terminal(),
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+    }
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::archive::{EntryKind, PaketArchive};
+use crate::install::Installer;
+use crate::placeholder::{self, HomeTarget};
+use crate::Result;
+
+/// An entry of `data.tar.gz` inside a .paket file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ContentEntry {
+    /// Relative path in `data.tar.gz` like `usr/bin/helloworld` or `$USER_HOME/.fonts/hack`.
+    pub path: PathBuf,
+
+    pub kind: EntryKind,
+
+    /// Size of the file content in bytes. `0` for folders and symlinks.
+    pub size: u64,
+
+    /// Permission bits like `0o755`.
+    pub mode: u32,
+
+    /// Target of the symlink.
+    pub link_name: Option<PathBuf>,
+
+    /// Paths in the installed system which the entry will be installed to.
+    ///
+    /// Entries under placeholder folders like `$USER_HOME` are installed to every home folder chosen by `home_policy`.
+    pub targets: Vec<PathBuf>,
+}
+
+/// List the entries of `data.tar.gz` inside a .paket file without installing it. `SHA256SUM` is checked first.
+///
+/// Example:
+/// ```rust,no_run
+/// use std::path::Path;
+/// use libpaket::install::Installer;
+///
+/// let entries = libpaket::contents::paket_contents(&Installer::default(), Path::new("helloworld_0.1.0.paket")).unwrap();
+/// for entry in entries {
+///     println!("{} -> {:?}", entry.path.display(), entry.targets);
+/// }
+/// ```
+pub fn paket_contents(installer: &Installer, paket_path: &Path) -> Result<Vec<ContentEntry>> {
+    let mut home_targets: Option<Vec<HomeTarget>> = None;
+    let mut contents = Vec::new();
+
+    let mut archive = PaketArchive::open(paket_path)?;
+    archive.verify_checksum()?;
+    let mut payload = archive.payload()?;
+    for entry in payload.entries()? {
+        let entry = entry?;
+        let path = entry.path;
+
+        if home_targets.is_none() && placeholder::split_placeholder(&path).is_some() {
+            home_targets = Some(installer.home_targets()?);
+        }
+        let targets = installer
+            .entry_targets(&path, home_targets.as_deref().unwrap_or(&[]))?
+            .into_iter()
+            .map(|(target, _)| target)
+            .collect();
+
+        contents.push(ContentEntry {
+            kind: entry.kind,
+            size: entry.content.len() as u64,
+            mode: entry.mode,
+            link_name: entry.link_name,
+            targets,
+            path,
+        });
+    }
+
+    Ok(contents)
+}
+
+/// Extract `data.tar.gz` inside a .paket file into a folder without installing it.
+///
+/// Paths are kept as in the paket, placeholder folders like `$USER_HOME` are not replaced.
+/// The paket database is not changed and the maintainer scripts don't run. `SHA256SUM` is checked before anything is
+/// extracted.
+pub fn extract_paket(paket_path: &Path, output_dir: &Path) -> Result<()> {
+    let mut archive = PaketArchive::open(paket_path)?;
+    archive.verify_checksum()?;
+
+    fs::create_dir_all(output_dir)?;
+
+    archive.payload()?.unpack_in(output_dir)
+}
@@ -1,5 +1,8 @@
-use semver::Version;
-use std::collections::HashSet;
+use semver::{Version, VersionReq};
+use std::collections::{HashMap, HashSet};
+
+use crate::toml_structs::paket_toml::{Config, PackageType};
+use crate::{PaketError, Result};
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct Dependency {
@@ -44,3 +47,168 @@ impl Dependency {
         unique_dependency_names
     }
 }
+
+/// Dependencies of a paket as `(name, version requirement)` pairs.
+///
+/// `[dependencies.development]` is only required by the source code pakets.
+///
+/// Example:
+/// ```rust
+/// use libpaket::dependency::required_dependencies;
+/// use libpaket::toml_structs::paket_toml::read_config_from_str;
+///
+/// let config = read_config_from_str(r#"
+/// [package]
+/// name = "helloworld-script"
+/// type = "script"
+/// version = "0.1.0"
+/// maintainers = ["Emin Fedar <eminfedar@gmail.com>"]
+/// description = "Hello world"
+/// license = "MIT"
+/// architectures = ["any"]
+///
+/// [dependencies.application]
+/// python3 = "3.11"
+///
+/// [script]
+/// executable = "main.py"
+/// sources_folder = "src"
+/// "#).unwrap();
+///
+/// let dependencies = required_dependencies(&config).unwrap();
+/// assert_eq!(dependencies[0].0, "python3");
+/// assert!(dependencies[0].1.matches(&"3.11.4".parse().unwrap()));
+/// ```
+pub fn required_dependencies(config: &Config) -> Result<Vec<(String, VersionReq)>> {
+    let dependencies = match &config.dependencies {
+        Some(d) => d,
+        None => return Ok(Vec::new()),
+    };
+
+    let is_source_code = matches!(
+        config.package.package_type,
+        PackageType::ApplicationSourceCode | PackageType::LibrarySourceCode
+    );
+
+    parse_requirements(
+        config,
+        &[
+            dependencies.application.as_ref(),
+            dependencies.library.as_ref(),
+            dependencies.development.as_ref().filter(|_| is_source_code),
+        ],
+    )
+}
+
+/// Recommended pakets of a paket as `(name, version requirement)` pairs. See: `paket_toml::Dependencies::recommended`
+pub fn recommended_dependencies(config: &Config) -> Result<Vec<(String, VersionReq)>> {
+    match &config.dependencies {
+        Some(d) => parse_requirements(config, &[d.recommended.as_ref()]),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Parse the `name = "version requirement"` pairs of the dependency tables.
+fn parse_requirements(
+    config: &Config,
+    tables: &[Option<&toml::Table>],
+) -> Result<Vec<(String, VersionReq)>> {
+    let mut requirements = Vec::new();
+    for (name, value) in tables.iter().flatten().flat_map(|t| t.iter()) {
+        let requirement = value
+            .as_str()
+            .and_then(|v| VersionReq::parse(v).ok())
+            .ok_or_else(|| {
+                PaketError::DependencyError(format!(
+                    "Invalid version requirement of {name} in {}: {value}",
+                    config.package.name
+                ))
+            })?;
+
+        requirements.push((name.clone(), requirement));
+    }
+
+    Ok(requirements)
+}
+
+/// Sort the pakets to install so every paket comes after its dependencies.
+///
+/// Dependencies must be satisfied by the `installed` pakets (name -> version) or the pakets themselves.
+///
+/// Returns the indexes of `pakets` in the installation order.
+pub fn resolve_install_order(
+    pakets: &[&Config],
+    installed: &HashMap<String, Version>,
+) -> Result<Vec<usize>> {
+    // Dependencies of each paket which are in the `pakets` list
+    let mut dependency_indexes: Vec<Vec<usize>> = Vec::with_capacity(pakets.len());
+
+    for config in pakets {
+        let mut indexes = Vec::new();
+
+        for (name, requirement) in required_dependencies(config)? {
+            let in_pakets = pakets.iter().position(|p| {
+                p.package.name == name
+                    && Version::parse(&p.package.version).is_ok_and(|v| requirement.matches(&v))
+            });
+
+            match in_pakets {
+                Some(i) => indexes.push(i),
+                None if installed.get(&name).is_some_and(|v| requirement.matches(v)) => {}
+                None => {
+                    return Err(PaketError::DependencyError(format!(
+                        "{name} ({requirement}) required by {} is not found",
+                        config.package.name
+                    )))
+                }
+            }
+        }
+
+        dependency_indexes.push(indexes);
+    }
+
+    // Depth first topological sort
+    let mut order = Vec::with_capacity(pakets.len());
+    let mut states = vec![VisitState::NotVisited; pakets.len()];
+
+    fn visit(
+        i: usize,
+        pakets: &[&Config],
+        dependency_indexes: &[Vec<usize>],
+        states: &mut [VisitState],
+        order: &mut Vec<usize>,
+    ) -> Result<()> {
+        match states[i] {
+            VisitState::Done => return Ok(()),
+            VisitState::Visiting => {
+                return Err(PaketError::DependencyError(format!(
+                    "Circular dependency on {}",
+                    pakets[i].package.name
+                )))
+            }
+            VisitState::NotVisited => (),
+        }
+
+        states[i] = VisitState::Visiting;
+        for &d in &dependency_indexes[i] {
+            visit(d, pakets, dependency_indexes, states, order)?;
+        }
+        states[i] = VisitState::Done;
+        order.push(i);
+
+        Ok(())
+    }
+
+    for i in 0..pakets.len() {
+        visit(i, pakets, &dependency_indexes, &mut states, &mut order)?;
+    }
+
+    Ok(order)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    NotVisited,
+    Visiting,
+    Done,
+}
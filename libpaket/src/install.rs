@@ -1,14 +1,22 @@
 use std::cmp::Ordering;
-use std::fs::{self, DirEntry, File};
-use std::io::Read;
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{Read, Seek};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::archive::{EntryKind, PaketArchive, PayloadEntry};
+use crate::dependency;
+use crate::owners;
+use crate::placeholder::{self, HomeTarget, Placeholder, UserHomePolicy};
+use crate::scripts::{PaketScripts, ScriptEnvironment};
+use crate::sha256;
+use crate::toml_structs::installed_toml::{InstalledFile, InstalledInfo};
 use crate::toml_structs::paket_toml::{self, Config};
-use crate::toml_structs::paket_toml::{read_config_from_toml, PackageType};
-use flate2::write::GzEncoder;
-use flate2::Compression;
-use once_cell::sync::Lazy;
-use tar::{Archive, Entries};
+use crate::toml_structs::paket_toml::{read_config_from_toml, PackageType, ScriptPhase};
+use crate::transaction::Transaction;
+use crate::triggers;
 use toml;
 
 use crate::{PaketError, Result};
@@ -22,174 +30,1111 @@ pub enum PaketExistance {
     HigherVersionInstalled,
     SameVersionInstalled,
 }
-pub enum DependencyStatus {
-    Valid,
-    NotValid(String),
-}
 
-pub struct InstallInformation {
-    paket_existance_status: PaketExistance,
-    dependency_status: DependencyStatus,
+/// Paket database folder relative to the root folder.
+const STATE_FOLDER: &str = "var/lib/paket";
+
+/// Backups of the running install or remove operation in the state folder.
+const TRANSACTION_FOLDER: &str = "transaction";
+
+/// Which layout the pakets are installed with.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum InstallTarget {
+    /// System wide installation to the root folder. Requires root for `/`.
+    #[default]
+    System,
+
+    /// Installation for the current user only, without root:
+    /// - `/usr/...` files are installed to `~/.local/...` (e.g. `/usr/bin/<executable>` -> `~/.local/bin/<executable>`)
+    /// - `$USER_HOME` files are installed to the home folder of the current user.
+    /// - The database is stored in `$XDG_DATA_HOME/paket` (`~/.local/share/paket` by default)
+    User,
 }
 
-// === Static Paths ===
-static BASE_PAKET_FOLDER: Lazy<PathBuf> = Lazy::new(|| Path::new("/var/lib/paket").to_path_buf());
-static INSTALLED_PAKETS_FOLDER: Lazy<PathBuf> = Lazy::new(|| BASE_PAKET_FOLDER.join("installed"));
+/// Options of the installation.
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Home folders to install placeholder folders like `$USER_HOME` into.
+    pub home_policy: UserHomePolicy,
 
-static INSTALLED_APPLICATIONS_FOLDER: Lazy<PathBuf> =
-    Lazy::new(|| INSTALLED_PAKETS_FOLDER.join("application"));
-static INSTALLED_LIBRARY_FOLDER: Lazy<PathBuf> =
-    Lazy::new(|| INSTALLED_PAKETS_FOLDER.join("library"));
-static INSTALLED_DEVELOPMENT_LIBRARY_FOLDER: Lazy<PathBuf> =
-    Lazy::new(|| INSTALLED_PAKETS_FOLDER.join("development_library"));
-static INSTALLED_SCRIPT_FOLDER: Lazy<PathBuf> =
-    Lazy::new(|| INSTALLED_PAKETS_FOLDER.join("script"));
-static INSTALLED_CONFIGURATIONS_FOLDER: Lazy<PathBuf> =
-    Lazy::new(|| INSTALLED_PAKETS_FOLDER.join("configuration"));
-static INSTALLED_APPLICATION_SOURCE_CODES_FOLDER: Lazy<PathBuf> =
-    Lazy::new(|| INSTALLED_PAKETS_FOLDER.join("application_source_code"));
-static INSTALLED_LIBRARY_SOURCE_CODES_FOLDER: Lazy<PathBuf> =
-    Lazy::new(|| INSTALLED_PAKETS_FOLDER.join("library_source_code"));
+    /// Install pakets even if their dependencies are not installed.
+    pub skip_dependency_check: bool,
 
-// === Functions ===
-pub fn unlock_paket_operations() -> Result<()> {
-    Ok(fs::remove_file(BASE_PAKET_FOLDER.join("lock"))?)
+    /// Remove the configuration files too while removing pakets. (They are kept by default)
+    pub purge_config_files: bool,
+
+    /// Overwrite the files owned by other installed pakets. See: `paket_toml::Package::replaces`
+    pub force_overwrite: bool,
+
+    /// Mark the new installations as dependencies of other pakets instead of explicitly installed pakets.
+    pub install_as_dependency: bool,
+
+    /// `LANG` of the maintainer scripts and the triggers. The environment of paket is used if not given.
+    pub language: Option<String>,
 }
 
-pub fn lock_paket_operations() -> Result<()> {
-    let _created_file = File::create(BASE_PAKET_FOLDER.join("lock"))?;
+/// Installs and removes pakets on a root folder with a paket database in a state folder.
+///
+/// Example:
+/// ```rust,no_run
+/// use std::path::PathBuf;
+/// use libpaket::install::Installer;
+///
+/// // Install into a chroot, the database will be in ./rootfs/var/lib/paket
+/// let installer = Installer::new("./rootfs");
+/// installer.install_paket_files(&[PathBuf::from("helloworld_0.1.0.paket")]).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Installer {
+    target: InstallTarget,
+    root_dir: PathBuf,
+    state_dir: PathBuf,
+    options: InstallOptions,
+}
 
-    Ok(())
+impl Default for Installer {
+    /// System wide installer for `/`.
+    fn default() -> Self {
+        Installer::new("/")
+    }
 }
 
-fn read_paket_toml_inside_tar(entries: Entries<'_, File>) -> Result<Config> {
-    let paket_toml = entries.filter_map(|entry| entry.ok()).find(|entry| {
-        entry
-            .path()
-            .is_ok_and(|f| f.file_name().unwrap() == "Paket.toml")
-    });
+impl Installer {
+    /// System wide installer for the `root_dir`. Database is stored in `<root_dir>/var/lib/paket`.
+    pub fn new(root_dir: impl Into<PathBuf>) -> Installer {
+        let root_dir = root_dir.into();
 
-    let mut paket_toml = match paket_toml {
-        Some(p) => p,
-        None => return Err(PaketError::FileNotFound(String::from("Paket.toml"))),
-    };
+        Installer {
+            target: InstallTarget::System,
+            state_dir: root_dir.join(STATE_FOLDER),
+            root_dir,
+            options: InstallOptions::default(),
+        }
+    }
 
-    let mut toml_content = String::new();
+    /// Installer for the current user without root. See: `InstallTarget::User`
+    pub fn for_user() -> Result<Installer> {
+        let state_dir = match std::env::var("XDG_DATA_HOME") {
+            Ok(data_home) if !data_home.is_empty() => Path::new(&data_home).join("paket"),
+            _ => user_home()?.join(".local/share/paket"),
+        };
 
-    paket_toml.read_to_string(&mut toml_content)?;
+        Ok(Installer {
+            target: InstallTarget::User,
+            root_dir: PathBuf::from("/"),
+            state_dir,
+            options: InstallOptions::default(),
+        })
+    }
 
-    toml::from_str(&toml_content).map_err(|e| PaketError::TomlParseError(e.message().to_string()))
-}
+    /// Store the paket database in another folder.
+    pub fn with_state_dir(mut self, state_dir: impl Into<PathBuf>) -> Installer {
+        self.state_dir = state_dir.into();
+        self
+    }
 
-fn check_installed_version(config: &Config) -> Result<PaketExistance> {
-    let installed_folder = match config.package.package_type {
-        PackageType::Application => &INSTALLED_APPLICATIONS_FOLDER,
-        PackageType::Script => &INSTALLED_SCRIPT_FOLDER,
+    pub fn with_options(mut self, options: InstallOptions) -> Installer {
+        self.options = options;
+        self
+    }
 
-        PackageType::Library => &INSTALLED_LIBRARY_FOLDER,
-        PackageType::DevelopmentLibrary => &INSTALLED_DEVELOPMENT_LIBRARY_FOLDER,
+    pub fn get_target(&self) -> InstallTarget {
+        self.target
+    }
 
-        PackageType::ApplicationSourceCode => &INSTALLED_APPLICATION_SOURCE_CODES_FOLDER,
-        PackageType::LibrarySourceCode => &INSTALLED_LIBRARY_SOURCE_CODES_FOLDER,
+    pub fn get_root_dir(&self) -> &Path {
+        &self.root_dir
+    }
 
-        PackageType::Configuration => &INSTALLED_CONFIGURATIONS_FOLDER,
-    };
+    pub fn get_state_dir(&self) -> &Path {
+        &self.state_dir
+    }
 
-    let installed_filepath = installed_folder.join(config.get_paket_archive_name());
-    if installed_filepath.exists() {
-        // Same Paket is already installed!
-        return Ok(PaketExistance::SameVersionInstalled);
+    pub fn get_options(&self) -> &InstallOptions {
+        &self.options
     }
 
-    // Check version difference with installed paket:
-    let paket_basename = format!("{}_", &config.package.name);
-    let mut paket_with_same_basename: Option<PathBuf> = None;
-    for dir_entry in fs::read_dir(installed_folder.as_path())? {
-        let e = dir_entry?;
+    pub fn unlock_paket_operations(&self) -> Result<()> {
+        Ok(fs::remove_file(self.state_dir.join("lock"))?)
+    }
 
-        let filename = e.file_name().to_string_lossy().to_string();
-        if let Some(0) = filename.find(&paket_basename) {
-            paket_with_same_basename = Some(e.path());
-            break;
+    pub fn lock_paket_operations(&self) -> Result<()> {
+        fs::create_dir_all(&self.state_dir)?;
+        let _created_file = File::create(self.state_dir.join("lock"))?;
+
+        Ok(())
+    }
+
+    /// Create the empty paket database folders in the state folder.
+    pub fn init_database(&self) -> Result<()> {
+        for package_type in PackageType::all() {
+            fs::create_dir_all(self.installed_folder(package_type))?;
         }
+
+        Ok(())
     }
 
-    if let Some(paket_path) = paket_with_same_basename {
-        // There is a package with the same name.
-        let installed_paket = read_config_from_toml(&paket_path)?;
+    /// Read `Paket.toml` of all installed pakets.
+    pub fn installed_pakets(&self) -> Result<Vec<Config>> {
+        let installed_pakets_folder = self.installed_pakets_folder();
+        if !installed_pakets_folder.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut installed_pakets = Vec::new();
+        for type_folder in fs::read_dir(installed_pakets_folder)? {
+            for record_folder in fs::read_dir(type_folder?.path())? {
+                let paket_toml_path = record_folder?.path().join("Paket.toml");
+
+                installed_pakets.push(read_config_from_toml(&paket_toml_path)?);
+            }
+        }
+
+        Ok(installed_pakets)
+    }
+
+    /// Installation information of a paket, read from its `Installed.toml`.
+    pub fn installed_info(&self, paket_name: &str) -> Result<InstalledInfo> {
+        match self.find_installed_paket(paket_name)? {
+            Some(record_folder) => read_installed_info(&record_folder),
+            None => Err(PaketError::NotInstalled(paket_name.to_string())),
+        }
+    }
+
+    /// Files installed by a paket, read from its `Installed.toml`.
+    pub fn installed_files(&self, paket_name: &str) -> Result<Vec<InstalledFile>> {
+        Ok(self.installed_info(paket_name)?.files)
+    }
+
+    /// Versions of the installed pakets by their names.
+    pub fn installed_versions(&self) -> Result<HashMap<String, Version>> {
+        Ok(self
+            .installed_pakets()?
+            .into_iter()
+            .filter_map(|c| Some((c.package.name, Version::parse(&c.package.version).ok()?)))
+            .collect())
+    }
+
+    fn installed_pakets_folder(&self) -> PathBuf {
+        self.state_dir.join("installed")
+    }
+
+    fn installed_folder(&self, package_type: &PackageType) -> PathBuf {
+        self.installed_pakets_folder().join(package_type.name())
+    }
+
+    /// Convert an absolute path in the installed system (e.g. `/usr/bin/app`) to the real path under the root folder.
+    pub fn host_path(&self, system_path: impl AsRef<Path>) -> PathBuf {
+        let system_path = system_path.as_ref();
+
+        self.root_dir
+            .join(system_path.strip_prefix("/").unwrap_or(system_path))
+    }
+
+    /// Home folders in the installed system to install the placeholder files like `$USER_HOME` into.
+    pub(crate) fn home_targets(&self) -> Result<Vec<HomeTarget>> {
+        match self.target {
+            InstallTarget::System => {
+                placeholder::home_targets(&self.root_dir, self.options.home_policy)
+            }
+            InstallTarget::User => Ok(vec![HomeTarget {
+                user: None,
+                home: user_home()?,
+            }]),
+        }
+    }
+
+    /// Get the path in the installed system of a file in data.tar.gz which is not under a placeholder folder.
+    ///
+    /// Uses the same `/usr/...` layout which `build::create_data_tar_gz` creates.
+    pub(crate) fn system_path(&self, entry_path: &Path) -> Result<PathBuf> {
+        match self.target {
+            InstallTarget::System => Ok(Path::new("/").join(entry_path)),
+            InstallTarget::User => match entry_path.strip_prefix("usr") {
+                Ok(rest) => Ok(user_home()?.join(".local").join(rest)),
+                Err(_) => Err(PaketError::NotAllowedInUserMode(
+                    entry_path.to_string_lossy().to_string(),
+                )),
+            },
+        }
+    }
+
+    /// Find the installed record folder of a paket by its name in all paket types.
+    fn find_installed_paket(&self, paket_name: &str) -> Result<Option<PathBuf>> {
+        let installed_pakets_folder = self.installed_pakets_folder();
+        if !installed_pakets_folder.exists() {
+            return Ok(None);
+        }
+
+        for dir_entry in fs::read_dir(installed_pakets_folder)? {
+            let type_folder = dir_entry?.path();
+
+            if let Some(p) = find_paket_in_folder(&type_folder, paket_name)? {
+                return Ok(Some(p));
+            }
+        }
+
+        Ok(None)
+    }
 
-        let currently_installed_version = Version::parse(&installed_paket.package.version).unwrap();
-        let new_version = Version::parse(&config.package.version).unwrap();
+    fn check_installed_version(&self, config: &Config) -> Result<PaketExistance> {
+        let installed_folder = self.installed_folder(&config.package.package_type);
 
-        let installed_version_diff = match currently_installed_version.cmp(&new_version) {
-            Ordering::Less => PaketExistance::LowerVersionInstalled,
-            Ordering::Equal => PaketExistance::SameVersionInstalled,
-            Ordering::Greater => PaketExistance::HigherVersionInstalled,
+        let installed_filepath = installed_folder.join(config.get_paket_full_name());
+        if installed_filepath.exists() {
+            // Same Paket is already installed!
+            return Ok(PaketExistance::SameVersionInstalled);
+        }
+
+        // Check version difference with installed paket:
+        let paket_with_same_basename =
+            find_paket_in_folder(&installed_folder, &config.package.name)?;
+
+        if let Some(paket_path) = paket_with_same_basename {
+            // There is a package with the same name.
+            let installed_paket = read_config_from_toml(&paket_path.join("Paket.toml"))?;
+
+            let parse_version = |config: &Config| {
+                Version::parse(&config.package.version).map_err(|e| {
+                    PaketError::TomlParseError(format!(
+                        "invalid version {} of {}: {e}",
+                        config.package.version, config.package.name
+                    ))
+                })
+            };
+            let currently_installed_version = parse_version(&installed_paket)?;
+            let new_version = parse_version(config)?;
+
+            let installed_version_diff = match currently_installed_version.cmp(&new_version) {
+                Ordering::Less => PaketExistance::LowerVersionInstalled,
+                Ordering::Equal => PaketExistance::SameVersionInstalled,
+                Ordering::Greater => PaketExistance::HigherVersionInstalled,
+            };
+
+            Ok(installed_version_diff)
+        } else {
+            Ok(PaketExistance::NotExists)
+        }
+    }
+
+    /// Paths in the installed system of a file in data.tar.gz with the users which own them.
+    ///
+    /// Files under placeholder folders like `$USER_HOME` are installed into every home folder in `home_targets`.
+    pub(crate) fn entry_targets<'h>(
+        &self,
+        entry_path: &Path,
+        home_targets: &'h [HomeTarget],
+    ) -> Result<Vec<(PathBuf, Option<&'h placeholder::User>)>> {
+        match placeholder::split_placeholder(entry_path) {
+            Some((placeholder, rest)) => Ok(home_targets
+                .iter()
+                .map(|t| {
+                    let system_path = placeholder.path_in_home(&t.home).join(&rest);
+
+                    (system_path, t.user.as_ref())
+                })
+                .collect()),
+            None => Ok(vec![(self.system_path(entry_path)?, None)]),
+        }
+    }
+
+    /// Folder which a target path in the host must stay in: the home folder for the files in the home folders,
+    /// otherwise the root folder. See: `check_target_path`
+    fn target_base(&self, target: &Path, home_targets: &[HomeTarget]) -> Result<PathBuf> {
+        let mut bases = Vec::new();
+        for home_target in home_targets {
+            let home = self.host_path(&home_target.home);
+
+            // XDG folders can be out of the home folder. See: `Placeholder::path_in_home`
+            for placeholder in [Placeholder::XdgConfigHome, Placeholder::XdgDataHome] {
+                let folder = self.host_path(placeholder.path_in_home(&home_target.home));
+                if !folder.starts_with(&home) {
+                    bases.push(folder);
+                }
+            }
+            bases.push(home);
+        }
+        if self.target == InstallTarget::User {
+            bases.push(user_home()?);
+        }
+        bases.push(self.root_dir.clone());
+
+        bases
+            .into_iter()
+            .filter(|b| target.starts_with(b))
+            .max_by_key(|b| b.components().count())
+            .ok_or_else(|| PaketError::InvalidPaketPath(target.to_string_lossy().to_string()))
+    }
+
+    /// Find the files in `data.tar.gz` which are owned by other installed pakets.
+    ///
+    /// Files of the pakets in `replaces` can be overwritten, otherwise the installation fails with a conflict report.
+    /// Returns the paths to overwrite with their current owners.
+    fn check_file_conflicts<R: Read + Seek>(
+        &self,
+        archive: &mut PaketArchive<R>,
+    ) -> Result<Vec<(String, Vec<String>)>> {
+        let index = owners::read_index(self)?;
+        let paket_name = archive.get_config().package.name.clone();
+        let replaces = archive
+            .get_config()
+            .package
+            .replaces
+            .clone()
+            .unwrap_or_default();
+
+        let mut home_targets: Option<Vec<HomeTarget>> = None;
+        let mut overwritten = Vec::new();
+        let mut conflicts = Vec::new();
+
+        let mut payload = archive.payload()?;
+        for entry in payload.entries()? {
+            let entry = entry?;
+
+            // Folders are shared by the pakets
+            if entry.kind == EntryKind::Folder {
+                continue;
+            }
+
+            if home_targets.is_none() && placeholder::split_placeholder(&entry.path).is_some() {
+                home_targets = Some(self.home_targets()?);
+            }
+
+            for (system_path, _) in
+                self.entry_targets(&entry.path, home_targets.as_deref().unwrap_or(&[]))?
+            {
+                let path = system_path.to_string_lossy().to_string();
+                let other_owners: Vec<String> = index
+                    .owners(&path)
+                    .iter()
+                    .filter(|o| **o != paket_name)
+                    .cloned()
+                    .collect();
+
+                if other_owners.is_empty() {
+                    continue;
+                }
+
+                if self.options.force_overwrite || other_owners.iter().all(|o| replaces.contains(o))
+                {
+                    overwritten.push((path, other_owners));
+                } else {
+                    conflicts.push(format!("{path} ({})", other_owners.join(", ")));
+                }
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(PaketError::FileConflict(conflicts.join(", ")));
+        }
+
+        Ok(overwritten)
+    }
+
+    /// Remove the overwritten files from the installed records of their old owners.
+    fn disown_files(
+        &self,
+        overwritten: &[(String, Vec<String>)],
+        transaction: &mut Transaction,
+    ) -> Result<()> {
+        let mut paths_of_owners: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for (path, old_owners) in overwritten {
+            for owner in old_owners {
+                paths_of_owners.entry(owner).or_default().insert(path);
+            }
+        }
+
+        for (owner, paths) in paths_of_owners {
+            let Some(record_folder) = self.find_installed_paket(owner)? else {
+                continue;
+            };
+
+            let mut info = read_installed_info(&record_folder)?;
+            info.files.retain(|f| !paths.contains(f.path.as_str()));
+
+            let installed_toml_content = toml::to_string(&info)
+                .map_err(|e| PaketError::TomlSerializeError(e.to_string()))?;
+            transaction.backup(&record_folder.join("Installed.toml"))?;
+            fs::write(record_folder.join("Installed.toml"), installed_toml_content)?;
+            owners::add_paket_files(self, owner, &info.files)?;
+        }
+
+        Ok(())
+    }
+
+    /// Extract `data.tar.gz` inside the .paket file to the root folder.
+    ///
+    /// Files under placeholder folders like `$USER_HOME` are installed into the home folders chosen by `home_policy`.
+    ///
+    /// Configuration files which are modified locally are kept and the new version is written as `<file>.paketnew`.
+    /// `old_files` are the installed files of the old version to check if a configuration file is modified.
+    fn extract_data_tar_gz<R: Read + Seek>(
+        &self,
+        archive: &mut PaketArchive<R>,
+        old_files: &[InstalledFile],
+        transaction: &mut Transaction,
+    ) -> Result<Vec<InstalledFile>> {
+        let config = archive.get_config().clone();
+
+        let mut home_targets: Option<Vec<HomeTarget>> = None;
+        let mut installed_files = Vec::new();
+
+        let mut payload = archive.payload()?;
+        for entry in payload.entries()? {
+            let entry = entry?;
+            let entry_path = &entry.path;
+
+            // Paths in the installed system to write with their owners
+            if home_targets.is_none() && placeholder::split_placeholder(entry_path).is_some() {
+                home_targets = Some(self.home_targets()?);
+            }
+            let home_targets = home_targets.as_deref().unwrap_or(&[]);
+            let targets = self.entry_targets(entry_path, home_targets)?;
+
+            if entry.kind == EntryKind::Folder {
+                for (system_path, owner) in targets {
+                    let host_path = self.host_path(system_path);
+                    check_target_path(&self.target_base(&host_path, home_targets)?, &host_path)?;
+                    create_folders(&host_path, owner)?;
+                }
+                continue;
+            }
+
+            let sha256sum = match entry.kind {
+                EntryKind::File => Some(sha256::calculate_sha256(&entry.content)),
+                _ => None,
+            };
+            let is_config_file = entry.kind == EntryKind::File
+                && config.is_config_file(&match placeholder::split_placeholder(entry_path) {
+                    Some(_) => entry_path.clone(),
+                    None => Path::new("/").join(entry_path),
+                });
+
+            for (system_path, owner) in targets {
+                let path = system_path.to_string_lossy().to_string();
+                let host_path = self.host_path(&system_path);
+                let mut write_path = host_path.clone();
+                let mut file_sha256sum = sha256sum.clone();
+                let mut modified = false;
+
+                if is_config_file {
+                    // A file which was kept modified before is still the user's version
+                    let old_sha256sum = old_files
+                        .iter()
+                        .find(|f| f.path == path && !f.modified)
+                        .and_then(|f| f.sha256.as_deref());
+
+                    if is_modified_config_file(&host_path, sha256sum.as_deref(), old_sha256sum)? {
+                        write_path = paketnew_path(&host_path);
+                        println!(
+                            "Configuration file is modified, new version is saved as: {}",
+                            write_path.display()
+                        );
+
+                        // The modified file stays at the path, so it's recorded instead of the new version
+                        file_sha256sum = Some(sha256::calculate_sha256(&fs::read(&host_path)?));
+                        modified = true;
+                    }
+                }
+
+                check_target_path(&self.target_base(&write_path, home_targets)?, &write_path)?;
+                transaction.backup(&write_path)?;
+                write_entry(&entry, &write_path, owner)?;
+
+                // Record the mode and the owner to verify the file later
+                let metadata = match entry.kind {
+                    EntryKind::File => Some(fs::metadata(&host_path)?),
+                    _ => None,
+                };
+
+                installed_files.push(InstalledFile {
+                    path,
+                    user: owner.map(|u| u.name.clone()),
+                    sha256: file_sha256sum,
+                    mode: metadata.as_ref().map(|m| m.mode() & 0o7777),
+                    uid: metadata.as_ref().map(|m| m.uid()),
+                    gid: metadata.as_ref().map(|m| m.gid()),
+                    config: is_config_file,
+                    modified,
+                });
+            }
+        }
+
+        Ok(installed_files)
+    }
+
+    /// Remove the files installed by a paket. Files which are already removed are skipped.
+    ///
+    /// `<file>.paketnew` of the configuration files are removed too.
+    fn remove_installed_files<'a>(
+        &self,
+        files: impl IntoIterator<Item = &'a InstalledFile>,
+        transaction: &mut Transaction,
+    ) -> Result<()> {
+        for f in files {
+            let host_path = self.host_path(&f.path);
+            transaction.backup(&host_path)?;
+            remove_file_if_exists(&host_path)?;
+
+            if f.config {
+                let paketnew = paketnew_path(&host_path);
+                transaction.backup(&paketnew)?;
+                remove_file_if_exists(&paketnew)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write the installed record of a paket:
+    /// - <state_dir>/installed/<type>/<name>_<version>/Paket.toml
+    /// - <state_dir>/installed/<type>/<name>_<version>/Installed.toml
+    /// - <state_dir>/installed/<type>/<name>_<version>/scripts/<phase>
+    fn write_installed_record(
+        &self,
+        config: &Config,
+        paket_toml_content: &str,
+        installed_info: &InstalledInfo,
+        scripts: &PaketScripts,
+        transaction: &mut Transaction,
+    ) -> Result<PathBuf> {
+        let record_folder = self.record_folder(config);
+        transaction.backup(&record_folder)?;
+        fs::create_dir_all(&record_folder)?;
+        scripts.write_to_folder(&record_folder.join("scripts"))?;
+
+        let installed_toml_content = toml::to_string(installed_info)
+            .map_err(|e| PaketError::TomlSerializeError(e.to_string()))?;
+
+        fs::write(record_folder.join("Paket.toml"), paket_toml_content)?;
+        fs::write(record_folder.join("Installed.toml"), installed_toml_content)?;
+
+        Ok(record_folder)
+    }
+
+    /// Check if an installed file is changed after the installation by comparing its SHA256 with the installed record.
+    fn is_modified(&self, installed_file: &InstalledFile) -> Result<bool> {
+        let host_path = self.host_path(&installed_file.path);
+
+        match (&installed_file.sha256, host_path.is_file()) {
+            (Some(sha256sum), true) => {
+                Ok(sha256::calculate_sha256(&fs::read(&host_path)?) != *sha256sum)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Installed record folder of a paket: `<state_dir>/installed/<type>/<name>_<version>`
+    fn record_folder(&self, config: &Config) -> PathBuf {
+        self.installed_folder(&config.package.package_type)
+            .join(config.get_paket_full_name())
+    }
+
+    /// Environment of the maintainer scripts of a paket.
+    fn script_environment<'a>(
+        &'a self,
+        work_dir: &'a Path,
+        paket_name: &'a str,
+        old_version: Option<&'a str>,
+        new_version: Option<&'a str>,
+    ) -> ScriptEnvironment<'a> {
+        ScriptEnvironment {
+            root_dir: &self.root_dir,
+            work_dir,
+            paket_name,
+            old_version,
+            new_version,
+            language: self.options.language.as_deref(),
+        }
+    }
+
+    /// Start an install or remove operation. See: `finish_transaction`
+    fn start_transaction(&self) -> Result<Transaction> {
+        Transaction::new(self.state_dir.join(TRANSACTION_FOLDER))
+    }
+
+    /// Finish an install or remove operation with its result.
+    ///
+    /// If the operation succeeded, the triggers of the changed files run once.
+    /// Otherwise all of its changes are rolled back and no trigger runs, because the files are not changed anymore.
+    fn finish_transaction<T>(&self, result: Result<T>, transaction: Transaction) -> Result<T> {
+        let value = match result {
+            Ok(value) => value,
+            Err(e) => {
+                println!("Rolling back the changes...");
+                transaction.rollback()?;
+                owners::rebuild_index(self)?;
+
+                return Err(e);
+            }
+        };
+
+        transaction.commit()?;
+        self.run_triggers(&transaction.changed_paths)?;
+
+        Ok(value)
+    }
+
+    /// Run the built-in triggers and the triggers of the installed pakets which match the changed files.
+    fn run_triggers(&self, changed_paths: &[PathBuf]) -> Result<()> {
+        if changed_paths.is_empty() {
+            return Ok(());
+        }
+
+        let mut all_triggers = triggers::builtin_triggers(self.target);
+        for config in self.installed_pakets()? {
+            all_triggers.extend(config.triggers.unwrap_or_default());
+        }
+
+        for trigger in triggers::find_triggered(self, &all_triggers, changed_paths) {
+            println!("Running trigger: {}", trigger.name);
+            triggers::run_trigger(self, trigger)?;
+        }
+
+        Ok(())
+    }
+
+    /// Install a .paket file and run the triggers of its files.
+    pub fn install_paket(&self, paket_path: &Path) -> Result<PaketExistance> {
+        let mut transaction = self.start_transaction()?;
+        let result = self.install_paket_in_transaction(
+            paket_path,
+            self.options.install_as_dependency,
+            &mut transaction,
+        );
+
+        self.finish_transaction(result, transaction)
+    }
+
+    /// Install a .paket file as a part of the `transaction`.
+    fn install_paket_in_transaction(
+        &self,
+        paket_path: &Path,
+        as_dependency: bool,
+        transaction: &mut Transaction,
+    ) -> Result<PaketExistance> {
+        // Get the valid Paket.toml
+        let mut archive = PaketArchive::open(paket_path)?;
+        archive.verify_checksum()?;
+        let config = archive.get_config().clone();
+        let paket_toml_content = archive.get_paket_toml().to_string();
+
+        let installed_status = self.check_installed_version(&config)?;
+
+        match installed_status {
+            PaketExistance::HigherVersionInstalled | PaketExistance::SameVersionInstalled => {
+                // Do nothing.
+                return Ok(installed_status);
+            }
+            _ => (),
+        }
+
+        if !self.options.skip_dependency_check {
+            dependency::resolve_install_order(&[&config], &self.installed_versions()?)?;
+        }
+
+        // Upgrade: find the old version
+        let old_record_folder = match installed_status {
+            PaketExistance::LowerVersionInstalled => find_paket_in_folder(
+                &self.installed_folder(&config.package.package_type),
+                &config.package.name,
+            )?,
+            _ => None,
+        };
+        let old_version = match &old_record_folder {
+            Some(f) => Some(
+                read_config_from_toml(&f.join("Paket.toml"))?
+                    .package
+                    .version,
+            ),
+            None => None,
+        };
+
+        let scripts = archive.get_scripts().clone();
+        let work_dir = self.state_dir.join("tmp");
+        let script_environment = self.script_environment(
+            &work_dir,
+            &config.package.name,
+            old_version.as_deref(),
+            Some(&config.package.version),
+        );
+
+        let overwritten = self.check_file_conflicts(&mut archive)?;
+
+        scripts.run(ScriptPhase::PreInstall, &script_environment)?;
+
+        let old_info = match &old_record_folder {
+            Some(f) => read_installed_info(f)?,
+            None => InstalledInfo::default(),
+        };
+
+        let installed_files =
+            self.extract_data_tar_gz(&mut archive, &old_info.files, transaction)?;
+        transaction
+            .changed_paths
+            .extend(installed_files.iter().map(|f| PathBuf::from(&f.path)));
+
+        // Upgrade: remove the files of the old version which don't exist in the new version.
+        // Modified configuration files are kept.
+        if let Some(old_record_folder) = old_record_folder {
+            let new_paths: HashSet<&str> =
+                installed_files.iter().map(|f| f.path.as_str()).collect();
+            let mut removed_files: Vec<&InstalledFile> = Vec::new();
+            for f in old_info
+                .files
+                .iter()
+                .filter(|f| !new_paths.contains(f.path.as_str()))
+            {
+                if !(f.config && self.is_modified(f)?) {
+                    removed_files.push(f);
+                }
+            }
+
+            self.remove_installed_files(removed_files.iter().copied(), transaction)?;
+            transaction
+                .changed_paths
+                .extend(removed_files.iter().map(|f| PathBuf::from(&f.path)));
+            transaction.backup(&old_record_folder)?;
+            fs::remove_dir_all(old_record_folder)?;
+        }
+
+        self.disown_files(&overwritten, transaction)?;
+
+        // Upgrades keep the installation reason of the old version
+        let installed_info = InstalledInfo {
+            files: installed_files,
+            dependency: match old_version {
+                Some(_) => old_info.dependency,
+                None => as_dependency,
+            },
+            install_time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .map(|d| d.as_secs()),
+        };
+        self.write_installed_record(
+            &config,
+            &paket_toml_content,
+            &installed_info,
+            &scripts,
+            transaction,
+        )?;
+        owners::add_paket_files(self, &config.package.name, &installed_info.files)?;
+
+        match installed_status {
+            PaketExistance::LowerVersionInstalled => {
+                scripts.run(ScriptPhase::PostUpgrade, &script_environment)?
+            }
+            _ => scripts.run(ScriptPhase::PostInstall, &script_environment)?,
+        }
+
+        Ok(installed_status)
+    }
+
+    /// Paket installation steps:
+    /// 1. Read `Paket.toml` inside the archive and check if it's a valid Config.
+    /// 2. Check installed status of the package. If paket doesn't exist or have lower version, proceed.
+    /// 3. Check if dependency tree of the paket is valid. Pakets are installed after their dependencies.
+    /// 4. Check if the files of the paket are owned by other installed pakets. See: `paket_toml::Package::replaces`
+    /// 5. Extract `data.tar.gz` to the root folder. Placeholder folders like `$USER_HOME` are installed by `home_policy`.
+    /// 6. Write the installed record of the paket with the list of installed files.
+    ///
+    /// Maintainer scripts run before (`pre_install`) and after (`post_install` or `post_upgrade`) the files are extracted.
+    ///
+    /// All pakets are installed in one transaction. If a step of any paket fails (e.g. a script), the changes of the
+    /// pakets which are installed before it are rolled back too. The scripts which already ran are not undone.
+    ///
+    /// Triggers run once after all pakets are installed. See: `paket_toml::Trigger`
+    pub fn install_paket_files(&self, paket_path_list: &[PathBuf]) -> Result<()> {
+        self.install_paket_files_with_dependencies(paket_path_list, &[])
+    }
+
+    /// Install .paket files like `install_paket_files`, with the .paket files of their dependencies.
+    ///
+    /// New installations from `dependency_path_list` are marked as dependencies. (e.g. dependencies downloaded from the repositories)
+    pub fn install_paket_files_with_dependencies(
+        &self,
+        paket_path_list: &[PathBuf],
+        dependency_path_list: &[PathBuf],
+    ) -> Result<()> {
+        let paket_path_list: Vec<&PathBuf> =
+            paket_path_list.iter().chain(dependency_path_list).collect();
+
+        let configs = paket_path_list
+            .iter()
+            .map(|p| Ok(PaketArchive::open(p)?.get_config().clone()))
+            .collect::<Result<Vec<Config>>>()?;
+
+        let install_order: Vec<usize> = if self.options.skip_dependency_check {
+            (0..paket_path_list.len()).collect()
+        } else {
+            dependency::resolve_install_order(
+                &configs.iter().collect::<Vec<&Config>>(),
+                &self.installed_versions()?,
+            )?
         };
 
-        Ok(installed_version_diff)
-    } else {
-        Ok(PaketExistance::NotExists)
+        let mut transaction = self.start_transaction()?;
+        let mut result = Ok(());
+        for paket_path in install_order.into_iter().map(|i| paket_path_list[i]) {
+            let as_dependency =
+                self.options.install_as_dependency || dependency_path_list.contains(paket_path);
+
+            match self.install_paket_in_transaction(paket_path, as_dependency, &mut transaction) {
+                Ok(s) => println!("{:?} => {s:?}", paket_path.file_name()),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+
+        self.finish_transaction(result, transaction)
+    }
+
+    /// Remove an installed paket by its name.
+    ///
+    /// All files installed by the paket are removed, including the files installed into home folders of users.
+    ///
+    /// `pre_remove` script runs before the files are removed and `post_remove` runs after.
+    pub fn remove_paket(&self, paket_name: &str) -> Result<()> {
+        let mut transaction = self.start_transaction()?;
+        let result = self.remove_paket_in_transaction(paket_name, &mut transaction);
+
+        self.finish_transaction(result, transaction)
+    }
+
+    /// Remove an installed paket as a part of the `transaction`.
+    fn remove_paket_in_transaction(
+        &self,
+        paket_name: &str,
+        transaction: &mut Transaction,
+    ) -> Result<()> {
+        let record_folder = match self.find_installed_paket(paket_name)? {
+            Some(r) => r,
+            None => return Err(PaketError::NotInstalled(paket_name.to_string())),
+        };
+
+        let config = read_config_from_toml(&record_folder.join("Paket.toml"))?;
+        let scripts = PaketScripts::from_folder(&record_folder.join("scripts"))?;
+        let work_dir = self.state_dir.join("tmp");
+        let script_environment = self.script_environment(
+            &work_dir,
+            &config.package.name,
+            Some(&config.package.version),
+            None,
+        );
+
+        scripts.run(ScriptPhase::PreRemove, &script_environment)?;
+
+        // Configuration files are kept unless they are purged
+        let installed_info = read_installed_info(&record_folder)?;
+        let removed_files: Vec<&InstalledFile> = installed_info
+            .files
+            .iter()
+            .filter(|f| !f.config || self.options.purge_config_files)
+            .collect();
+        self.remove_installed_files(removed_files.iter().copied(), transaction)?;
+        transaction
+            .changed_paths
+            .extend(removed_files.iter().map(|f| PathBuf::from(&f.path)));
+
+        transaction.backup(&record_folder)?;
+        fs::remove_dir_all(record_folder)?;
+        owners::remove_paket_files(self, &config.package.name)?;
+
+        scripts.run(ScriptPhase::PostRemove, &script_environment)
+    }
+
+    /// Remove installed pakets by their names. Triggers run once after all pakets are removed.
+    pub fn remove_pakets(&self, paket_names: &[String]) -> Result<()> {
+        let mut transaction = self.start_transaction()?;
+        let result = paket_names
+            .iter()
+            .try_for_each(|name| self.remove_paket_in_transaction(name, &mut transaction));
+
+        self.finish_transaction(result, transaction)
     }
 }
 
-fn check_application_dependencies(dependency_list: &toml::Table) -> Result<DependencyStatus> {
-    for (key, value) in dependency_list {
-        println!("{key} => {value}");
+// === Functions ===
+fn user_home() -> Result<PathBuf> {
+    match std::env::var("HOME") {
+        Ok(home) if !home.is_empty() => Ok(PathBuf::from(home)),
+        _ => Err(PaketError::UserNotFound(
+            std::env::var("USER").unwrap_or_default(),
+        )),
+    }
+}
+
+/// Find the installed record folder (`<name>_<version>/`) of a paket in an installed folder.
+fn find_paket_in_folder(folder: &Path, paket_name: &str) -> Result<Option<PathBuf>> {
+    if !folder.exists() {
+        return Ok(None);
+    }
+
+    for dir_entry in fs::read_dir(folder)? {
+        let e = dir_entry?;
+
+        // Names can contain `_` but versions can't, so the version is after the last `_`
+        let filename = e.file_name().to_string_lossy().to_string();
+        let is_paket = filename
+            .rsplit_once('_')
+            .is_some_and(|(name, version)| name == paket_name && Version::parse(version).is_ok());
+        if is_paket {
+            return Ok(Some(e.path()));
+        }
     }
 
-    Ok(DependencyStatus::Valid)
+    Ok(None)
 }
 
-fn check_all_dependencies(config: &Config) -> Result<DependencyStatus> {
-    let dependencies = match &config.dependencies {
-        Some(d) => d,
-        None => return Ok(DependencyStatus::Valid), // No dependencies, directly valid
-    };
+/// Paths inside the data.tar.gz must be relative and must not go to the parent folders.
+pub(crate) fn check_paket_path(path: &Path) -> Result<()> {
+    let is_valid = path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
 
-    // Check application dependencies
-    if dependencies.application.is_some() {
-        check_application_dependencies(dependencies.application.as_ref().unwrap())?;
+    if !is_valid {
+        return Err(PaketError::InvalidPaketPath(
+            path.to_string_lossy().to_string(),
+        ));
     }
 
-    Ok(DependencyStatus::Valid)
+    Ok(())
 }
 
-pub fn install_paket(paket_path: &PathBuf) -> Result<PaketExistance> {
-    // Read base .tar archive:
-    let file = File::open(paket_path)?;
-    let mut ar = Archive::new(file);
-    let entries = ar.entries()?;
+/// Check that writing to `target` doesn't go out of `base`, the root folder or the home folder it's installed into.
+///
+/// Existing parent folders of the target in `base` must not be symlinks. Otherwise a paket could install a symlink
+/// like `usr/lib/x -> /` and then write `usr/lib/x/etc/passwd` through it, out of the root folder.
+fn check_target_path(base: &Path, target: &Path) -> Result<()> {
+    let invalid = || PaketError::InvalidPaketPath(target.to_string_lossy().to_string());
+    let relative = target.strip_prefix(base).map_err(|_| invalid())?;
+    if !base.exists() {
+        // Nothing is in the base folder to follow yet
+        return Ok(());
+    }
+
+    let mut parents = relative.components();
+    parents.next_back();
+
+    let mut parent = base.to_path_buf();
+    for component in parents {
+        parent.push(component);
+        match fs::symlink_metadata(&parent) {
+            Ok(metadata) if metadata.is_symlink() => return Err(invalid()),
+            Ok(_) => (),
+            Err(_) => {
+                // Missing folders are created
+                parent.pop();
+                break;
+            }
+        }
+    }
 
-    // Get the valid Paket.toml
-    let config: Config = read_paket_toml_inside_tar(entries)?;
+    match parent.canonicalize()?.starts_with(base.canonicalize()?) {
+        true => Ok(()),
+        false => Err(invalid()),
+    }
+}
 
-    let installed_status = check_installed_version(&config)?;
+/// Create the `folder` with its missing parents and give their ownership to the `owner` of the home folder.
+fn create_folders(folder: &Path, owner: Option<&placeholder::User>) -> Result<()> {
+    let mut missing_folders: Vec<&Path> = folder.ancestors().take_while(|p| !p.exists()).collect();
+    fs::create_dir_all(folder)?;
 
-    match installed_status {
-        PaketExistance::HigherVersionInstalled | PaketExistance::SameVersionInstalled => {
-            // Do nothing.
-            return Ok(installed_status);
+    if let Some(user) = owner {
+        missing_folders.reverse();
+        for folder in missing_folders {
+            std::os::unix::fs::chown(folder, Some(user.uid), Some(user.gid))?;
         }
-        _ => (),
     }
 
-    check_all_dependencies(&config)?;
+    Ok(())
+}
 
-    Ok(PaketExistance::NotExists)
+fn create_parent_folders(path: &Path, owner: Option<&placeholder::User>) -> Result<()> {
+    match path.parent() {
+        Some(parent) => create_folders(parent, owner),
+        None => Ok(()),
+    }
 }
 
-/// Paket installation steps:
-/// 1. Read `Paket.toml` inside the archive and check if it's a valid Config.
-/// 2. Check installed status of the package. If paket doesn't exist or have lower version, proceed.
-/// 3. Check if dependency tree of the paket is valid.
-pub fn install_paket_files(paket_path_list: &[PathBuf]) -> Result<()> {
-    for paket_path in paket_path_list {
-        match install_paket(paket_path) {
-            Ok(s) => println!("{:?} => {s:?}", paket_path.file_name()),
-            Err(e) => return Err(e),
+/// Write the content of a tar entry to the target path.
+///
+/// Files are written to a temporary file next to the target first, then renamed to the target.
+/// So running executables or libraries can be replaced safely.
+fn write_entry(
+    entry: &PayloadEntry,
+    target: &Path,
+    owner: Option<&placeholder::User>,
+) -> Result<()> {
+    create_parent_folders(target, owner)?;
+
+    let mut temp_name = target.as_os_str().to_os_string();
+    temp_name.push(".paket-tmp");
+    let temp_path = PathBuf::from(temp_name);
+
+    match entry.kind {
+        EntryKind::Symlink => {
+            let link_name = entry
+                .link_name
+                .as_deref()
+                .ok_or(PaketError::InvalidPaketPath(
+                    target.to_string_lossy().to_string(),
+                ))?;
+            let _ = fs::remove_file(&temp_path);
+            std::os::unix::fs::symlink(link_name, &temp_path)?;
+        }
+        _ => {
+            fs::write(&temp_path, &entry.content)?;
+            fs::set_permissions(&temp_path, fs::Permissions::from_mode(entry.mode))?;
         }
     }
 
+    if let Some(user) = owner {
+        std::os::unix::fs::lchown(&temp_path, Some(user.uid), Some(user.gid))?;
+    }
+
+    fs::rename(&temp_path, target)?;
+
     Ok(())
 }
+
+fn remove_file_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// `<file>.paketnew` path to save the new version of a modified configuration file.
+fn paketnew_path(path: &Path) -> PathBuf {
+    let mut paketnew = path.as_os_str().to_os_string();
+    paketnew.push(".paketnew");
+
+    PathBuf::from(paketnew)
+}
+
+/// Check if an existing configuration file is modified locally.
+///
+/// The file is not modified if it doesn't exist, or it is the same as the new or the old version of the paket.
+fn is_modified_config_file(
+    path: &Path,
+    new_sha256sum: Option<&str>,
+    old_sha256sum: Option<&str>,
+) -> Result<bool> {
+    if !path.is_file() {
+        return Ok(false);
+    }
+
+    let current_sha256sum = sha256::calculate_sha256(&fs::read(path)?);
+
+    Ok(Some(current_sha256sum.as_str()) != new_sha256sum
+        && Some(current_sha256sum.as_str()) != old_sha256sum)
+}
+
+fn read_installed_info(record_folder: &Path) -> Result<InstalledInfo> {
+    paket_toml::read_toml_file(&record_folder.join("Installed.toml"))
+}
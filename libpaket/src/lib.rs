@@ -1,26 +1,73 @@
+pub mod archive;
+mod atomic;
+pub mod bootstrap;
 pub mod build;
+pub mod cache;
+pub mod config;
+pub mod contents;
 pub mod dependency;
 pub mod install;
+pub mod oci;
+pub mod owners;
+pub mod placeholder;
+pub mod query;
+pub mod remote;
+pub mod repos;
+pub mod repository;
+pub mod scripts;
+pub mod search;
 pub mod sha256;
+pub mod signature;
 pub mod toml_structs;
+mod transaction;
+pub mod triggers;
+pub mod upgrade;
+pub mod verify;
 
 use std::fmt;
 
 #[allow(dead_code)]
 pub mod color {
-    pub const RESET: &str = "\x1b[0m"; // Sıfırla (rengi sıfırlar ve özellikleri kapatır)
-    pub const BOLD: &str = "\x1b[1m"; // Kalın
-    pub const DIM: &str = "\x1b[2m"; // İnce
-    pub const UNDERLINE: &str = "\x1b[4m"; // Alt Çizgi
-    pub const INVERSE: &str = "\x1b[7m"; // Ters (renklerin tersine çevrilmesi)
-    pub const STRIKETHROUGH: &str = "\x1b[9m"; // Üstü Çizili
-    pub const RED: &str = "\x1b[31m"; // Kırmızı
-    pub const GREEN: &str = "\x1b[32m"; // Yeşil
-    pub const YELLOW: &str = "\x1b[33m"; // Sarı
-    pub const BLUE: &str = "\x1b[34m"; // Mavi
-    pub const MAGENTA: &str = "\x1b[35m"; // Magenta (Pembe)
-    pub const CYAN: &str = "\x1b[36m"; // Cyan (Camgöbeği)
-    pub const WHITE: &str = "\x1b[37m"; // Beyaz
+    use std::fmt;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static ENABLED: AtomicBool = AtomicBool::new(true);
+
+    /// ANSI escape code which is written only if the colors are enabled. See: `set_enabled`
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Color(&'static str);
+
+    impl fmt::Display for Color {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match is_enabled() {
+                true => f.write_str(self.0),
+                false => Ok(()),
+            }
+        }
+    }
+
+    /// Enable or disable the colors of the whole program. (Enabled by default)
+    pub fn set_enabled(enabled: bool) {
+        ENABLED.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn is_enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    pub const RESET: Color = Color("\x1b[0m"); // Sıfırla (rengi sıfırlar ve özellikleri kapatır)
+    pub const BOLD: Color = Color("\x1b[1m"); // Kalın
+    pub const DIM: Color = Color("\x1b[2m"); // İnce
+    pub const UNDERLINE: Color = Color("\x1b[4m"); // Alt Çizgi
+    pub const INVERSE: Color = Color("\x1b[7m"); // Ters (renklerin tersine çevrilmesi)
+    pub const STRIKETHROUGH: Color = Color("\x1b[9m"); // Üstü Çizili
+    pub const RED: Color = Color("\x1b[31m"); // Kırmızı
+    pub const GREEN: Color = Color("\x1b[32m"); // Yeşil
+    pub const YELLOW: Color = Color("\x1b[33m"); // Sarı
+    pub const BLUE: Color = Color("\x1b[34m"); // Mavi
+    pub const MAGENTA: Color = Color("\x1b[35m"); // Magenta (Pembe)
+    pub const CYAN: Color = Color("\x1b[36m"); // Cyan (Camgöbeği)
+    pub const WHITE: Color = Color("\x1b[37m"); // Beyaz
 }
 use color::*;
 
@@ -31,7 +78,26 @@ pub enum PaketError {
     NotATomlFile(String),
     TomlFieldNotFound(String),
     TomlParseError(String),
+    TomlSerializeError(String),
+    JsonSerializeError(String),
     IOError(String),
+    InvalidPaketPath(String),
+    UserNotFound(String),
+    NotInstalled(String),
+    NotAllowedInUserMode(String),
+    DependencyError(String),
+    FolderNotEmpty(String),
+    ScriptError(String),
+    InvalidPattern(String),
+    FileConflict(String),
+    InvalidPaketArchive(String),
+    ChecksumMismatch(String),
+    InvalidSignature(String),
+    DownloadError(String),
+    PaketNotFound(String),
+    RepositoryNotUpdated(String),
+    UnsignedRepository(String),
+    InvalidConfig(String),
 }
 
 pub type Result<T> = std::result::Result<T, PaketError>;
@@ -48,11 +114,55 @@ impl fmt::Display for PaketError {
             PaketError::TomlParseError(s) => {
                 write!(f, "Paket.toml Parse Error: {BOLD}'{s}'{RESET}")
             }
+            PaketError::TomlSerializeError(s) => {
+                write!(f, "Toml Serialize Error: {BOLD}'{s}'{RESET}")
+            }
+            PaketError::JsonSerializeError(s) => {
+                write!(f, "Json Serialize Error: {BOLD}'{s}'{RESET}")
+            }
             PaketError::IOError(s) => write!(f, "IO Error -> {BOLD}'{s}'{RESET}"),
+            PaketError::InvalidPaketPath(s) => {
+                write!(f, "Invalid path inside the paket: {BOLD}'{s}'{RESET}")
+            }
+            PaketError::UserNotFound(s) => write!(f, "User not found: {BOLD}'{s}'{RESET}"),
+            PaketError::NotInstalled(s) => write!(f, "Paket is not installed: {BOLD}'{s}'{RESET}"),
+            PaketError::DependencyError(s) => write!(f, "Dependency Error: {BOLD}{s}{RESET}"),
+            PaketError::FolderNotEmpty(s) => write!(f, "Folder is not empty: {BOLD}'{s}'{RESET}"),
+            PaketError::ScriptError(s) => {
+                write!(f, "Maintainer script failed: {BOLD}'{s}'{RESET}")
+            }
+            PaketError::InvalidPattern(s) => write!(f, "Invalid pattern: {BOLD}'{s}'{RESET}"),
+            PaketError::InvalidPaketArchive(s) => {
+                write!(f, "Invalid .paket file: {BOLD}{s}{RESET}")
+            }
+            PaketError::ChecksumMismatch(s) => write!(f, "Checksum mismatch: {BOLD}{s}{RESET}"),
+            PaketError::InvalidSignature(s) => write!(f, "Invalid signature: {BOLD}{s}{RESET}"),
+            PaketError::DownloadError(s) => write!(f, "Download failed: {BOLD}{s}{RESET}"),
+            PaketError::PaketNotFound(s) => {
+                write!(f, "Paket not found in the repositories: {BOLD}'{s}'{RESET}")
+            }
+            PaketError::InvalidConfig(s) => write!(f, "Invalid configuration: {BOLD}{s}{RESET}"),
+            PaketError::RepositoryNotUpdated(s) => write!(
+                f,
+                "Index of the repository is not downloaded, run `paket update`: {BOLD}'{s}'{RESET}"
+            ),
+            PaketError::UnsignedRepository(s) => write!(
+                f,
+                "Repository has no trusted key, use `allow_unsigned = true` or `--allow-unsigned` to use it anyway: {BOLD}'{s}'{RESET}"
+            ),
+            PaketError::FileConflict(s) => {
+                write!(f, "Files are owned by other pakets: {BOLD}{s}{RESET}")
+            }
+            PaketError::NotAllowedInUserMode(s) => write!(
+                f,
+                "Only /usr and $USER_HOME files can be installed without root: {BOLD}'{s}'{RESET}"
+            ),
         }
     }
 }
 
+impl std::error::Error for PaketError {}
+
 impl From<std::io::Error> for PaketError {
     fn from(value: std::io::Error) -> Self {
         PaketError::IOError(value.to_string())
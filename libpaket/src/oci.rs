@@ -0,0 +1,464 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Map, Value};
+
+use crate::bootstrap;
+use crate::dependency;
+use crate::install::Installer;
+use crate::sha256;
+use crate::toml_structs::paket_toml::{self, Config, PackageType};
+use crate::{PaketError, Result};
+
+const MEDIA_TYPE_INDEX: &str = "application/vnd.oci.image.index.v1+json";
+const MEDIA_TYPE_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+const MEDIA_TYPE_CONFIG: &str = "application/vnd.oci.image.config.v1+json";
+const MEDIA_TYPE_LAYER: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
+
+const DEFAULT_PATH_ENV: &str = "PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+/// How the files of the root folder are split into the image layers.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum LayerMode {
+    /// A layer for each installed paket, in the installation order.
+    ///
+    /// The first layer contains the files which don't belong to any paket like the folders and the paket database.
+    #[default]
+    PerPaket,
+
+    /// Only one layer which contains the whole root folder.
+    Squashed,
+}
+
+/// Options of the exported OCI image.
+#[derive(Debug, Clone, Default)]
+pub struct OciExportOptions {
+    pub layer_mode: LayerMode,
+
+    /// Installed paket to take the image config (labels, entrypoint, architecture) from.
+    ///
+    /// The last paket in the installation order is used if not given.
+    pub config_paket: Option<String>,
+
+    /// `org.opencontainers.image.ref.name` of the image. Version of the config paket is used if not given. See: `sanitize_tag`
+    pub tag: Option<String>,
+}
+
+/// A layer blob which is written to `blobs/sha256/<digest>`
+struct Layer {
+    /// SHA256 of the uncompressed tar. (`rootfs.diff_ids` in the image config)
+    diff_id: String,
+
+    /// SHA256 of the compressed blob.
+    digest: String,
+
+    blob: Vec<u8>,
+    created_by: String,
+}
+
+impl Layer {
+    fn from_tar(tar: Vec<u8>, created_by: String) -> Result<Layer> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&tar)?;
+        let blob = encoder.finish()?;
+
+        Ok(Layer {
+            diff_id: sha256::calculate_sha256(&tar),
+            digest: sha256::calculate_sha256(&blob),
+            blob,
+            created_by,
+        })
+    }
+}
+
+/// Adds the files of the root folder to a layer tar with their parent folders.
+struct LayerBuilder<'a> {
+    root_dir: &'a Path,
+    tar_builder: tar::Builder<Vec<u8>>,
+    added_paths: HashSet<PathBuf>,
+}
+
+impl<'a> LayerBuilder<'a> {
+    fn new(root_dir: &'a Path) -> LayerBuilder<'a> {
+        let mut tar_builder = tar::Builder::new(Vec::new());
+        tar_builder.follow_symlinks(false);
+
+        LayerBuilder {
+            root_dir,
+            tar_builder,
+            added_paths: HashSet::new(),
+        }
+    }
+
+    /// Add a path relative to the root folder. Missing parent folders are added before it.
+    fn append(&mut self, relative_path: &Path) -> Result<()> {
+        if relative_path.as_os_str().is_empty() || self.added_paths.contains(relative_path) {
+            return Ok(());
+        }
+
+        if let Some(parent) = relative_path.parent() {
+            self.append(parent)?;
+        }
+
+        let host_path = self.root_dir.join(relative_path);
+        let file_type = fs::symlink_metadata(&host_path)?.file_type();
+
+        if file_type.is_dir() {
+            self.tar_builder.append_dir(relative_path, &host_path)?;
+        } else if file_type.is_file() || file_type.is_symlink() {
+            self.tar_builder
+                .append_path_with_name(&host_path, relative_path)?;
+        }
+        // Sockets, fifos and devices are not added to the image
+
+        self.added_paths.insert(relative_path.to_path_buf());
+
+        Ok(())
+    }
+
+    /// Add a folder and everything inside it except the `skipped` paths.
+    fn append_all(&mut self, relative_path: &Path, skipped: &HashSet<PathBuf>) -> Result<()> {
+        let host_path = self.root_dir.join(relative_path);
+        let mut entries: Vec<PathBuf> = fs::read_dir(&host_path)?
+            .map(|e| Ok(relative_path.join(e?.file_name())))
+            .collect::<io::Result<_>>()?;
+        entries.sort();
+
+        for entry in entries {
+            if skipped.contains(&entry) {
+                continue;
+            }
+
+            self.append(&entry)?;
+
+            if fs::symlink_metadata(self.root_dir.join(&entry))?.is_dir() {
+                self.append_all(&entry, skipped)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Vec<u8>> {
+        Ok(self.tar_builder.into_inner()?)
+    }
+}
+
+/// Convert Debian architecture names in Paket.toml to OCI architecture names.
+fn oci_architecture(architectures: &[String]) -> &'static str {
+    let architecture = architectures
+        .iter()
+        .find(|a| a.as_str() != "any")
+        .map(|a| a.as_str())
+        .unwrap_or(paket_toml::host_architecture());
+
+    match architecture {
+        "amd64" => "amd64",
+        "arm64" => "arm64",
+        "i386" => "386",
+        "armhf" | "armel" => "arm",
+        "riscv64" => "riscv64",
+        "ppc64el" => "ppc64le",
+        "s390x" => "s390x",
+        "loong64" => "loong64",
+        _ => "unknown",
+    }
+}
+
+/// Generate the OCI image config from Paket.toml of the config paket.
+fn image_config(config: Option<&Config>, layers: &[Layer]) -> Value {
+    let mut labels = Map::new();
+    let mut container_config = Map::new();
+    container_config.insert("Env".to_string(), json!([DEFAULT_PATH_ENV]));
+
+    if let Some(config) = config {
+        let package = &config.package;
+        labels.insert("org.opencontainers.image.title".into(), json!(package.name));
+        labels.insert(
+            "org.opencontainers.image.version".into(),
+            json!(package.version),
+        );
+        labels.insert(
+            "org.opencontainers.image.description".into(),
+            json!(package.description.trim()),
+        );
+        labels.insert(
+            "org.opencontainers.image.licenses".into(),
+            json!(package.license),
+        );
+        labels.insert(
+            "org.opencontainers.image.authors".into(),
+            json!(package.maintainers.join(", ")),
+        );
+        if let Some(homepage) = &package.homepage {
+            labels.insert("org.opencontainers.image.url".into(), json!(homepage));
+        }
+        if let Some(source_repository) = &package.source_repository {
+            labels.insert(
+                "org.opencontainers.image.source".into(),
+                json!(source_repository),
+            );
+        }
+
+        let executable = match package.package_type {
+            PackageType::Application => config.application.as_ref().map(|a| &a.executable),
+            PackageType::Script => config.script.as_ref().map(|s| &s.executable),
+            _ => None,
+        };
+        if let Some(executable) = executable {
+            container_config.insert(
+                "Entrypoint".to_string(),
+                json!([format!("/usr/bin/{executable}")]),
+            );
+        }
+    }
+    container_config.insert("Labels".to_string(), Value::Object(labels));
+
+    let architectures = config
+        .map(|c| c.package.architectures.clone())
+        .unwrap_or_default();
+
+    json!({
+        "architecture": oci_architecture(&architectures),
+        "os": "linux",
+        "config": container_config,
+        "rootfs": {
+            "type": "layers",
+            "diff_ids": layers.iter().map(|l| format!("sha256:{}", l.diff_id)).collect::<Vec<String>>(),
+        },
+        "history": layers.iter().map(|l| json!({ "created_by": l.created_by })).collect::<Vec<Value>>(),
+    })
+}
+
+/// Create the layers of the root folder of the installer.
+///
+/// Returns the layers with the installed pakets in the installation order.
+fn create_layers(
+    installer: &Installer,
+    layer_mode: LayerMode,
+) -> Result<(Vec<Layer>, Vec<Config>)> {
+    let root_dir = installer.get_root_dir();
+
+    let installed_pakets = installer.installed_pakets()?;
+    let install_order = dependency::resolve_install_order(
+        &installed_pakets.iter().collect::<Vec<&Config>>(),
+        &HashMap::new(),
+    )
+    // Pakets installed without their dependencies can't be sorted, keep them as they are
+    .unwrap_or_else(|_| (0..installed_pakets.len()).collect());
+
+    let mut ordered_pakets: Vec<Option<Config>> = installed_pakets.into_iter().map(Some).collect();
+    let ordered_pakets: Vec<Config> = install_order
+        .into_iter()
+        .filter_map(|i| ordered_pakets[i].take())
+        .collect();
+
+    if layer_mode == LayerMode::Squashed {
+        let mut layer = LayerBuilder::new(root_dir);
+        layer.append_all(Path::new(""), &HashSet::new())?;
+
+        let layer = Layer::from_tar(layer.finish()?, "paket export-oci".to_string())?;
+
+        return Ok((vec![layer], ordered_pakets));
+    }
+
+    // Files of every paket relative to the root folder
+    let mut paket_files: Vec<Vec<PathBuf>> = Vec::with_capacity(ordered_pakets.len());
+    for config in &ordered_pakets {
+        let files = installer
+            .installed_files(&config.package.name)?
+            .into_iter()
+            .map(|f| {
+                let host_path = installer.host_path(&f.path);
+                host_path
+                    .strip_prefix(root_dir)
+                    .map(|p| p.to_path_buf())
+                    .unwrap_or(host_path)
+            })
+            .filter(|p| root_dir.join(p).symlink_metadata().is_ok())
+            .collect();
+
+        paket_files.push(files);
+    }
+
+    let owned_files: HashSet<PathBuf> = paket_files.iter().flatten().cloned().collect();
+
+    // Base layer: everything which doesn't belong to a paket
+    let mut base_layer = LayerBuilder::new(root_dir);
+    base_layer.append_all(Path::new(""), &owned_files)?;
+    let mut layers = vec![Layer::from_tar(
+        base_layer.finish()?,
+        "paket bootstrap".to_string(),
+    )?];
+
+    for (config, files) in ordered_pakets.iter().zip(paket_files) {
+        let mut layer = LayerBuilder::new(root_dir);
+        for f in files {
+            layer.append(&f)?;
+        }
+
+        layers.push(Layer::from_tar(
+            layer.finish()?,
+            format!("paket install {}", config.get_paket_full_name()),
+        )?);
+    }
+
+    Ok((layers, ordered_pakets))
+}
+
+fn to_json(value: &Value) -> Result<Vec<u8>> {
+    serde_json::to_vec(value).map_err(|e| PaketError::JsonSerializeError(e.to_string()))
+}
+
+fn append_bytes(
+    tar_builder: &mut tar::Builder<File>,
+    data: &[u8],
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let mut header = tar::Header::new_ustar();
+    header.set_size(data.len() as u64);
+    header.set_entry_type(tar::EntryType::Regular);
+    header.set_path(path)?;
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    tar_builder.append(&header, data)
+}
+
+fn append_blob(tar_builder: &mut tar::Builder<File>, digest: &str, data: &[u8]) -> io::Result<()> {
+    append_bytes(tar_builder, data, format!("blobs/sha256/{digest}"))
+}
+
+/// Export the root folder of an installer as an OCI image layout tarball.
+///
+/// No container runtime is needed, the image can be loaded by any OCI compatible tool:
+/// ```sh
+/// skopeo copy oci-archive:image.tar docker-daemon:myimage:latest
+/// podman load -i image.tar
+/// ```
+///
+/// Example:
+/// ```rust,no_run
+/// use std::path::Path;
+/// use libpaket::install::Installer;
+/// use libpaket::oci::{export_oci_image, LayerMode, OciExportOptions};
+///
+/// let options = OciExportOptions {
+///     layer_mode: LayerMode::Squashed,
+///     ..Default::default()
+/// };
+/// export_oci_image(&Installer::new("./rootfs"), Path::new("image.tar"), &options).unwrap();
+/// ```
+pub fn export_oci_image(
+    installer: &Installer,
+    output: &Path,
+    options: &OciExportOptions,
+) -> Result<()> {
+    let (layers, ordered_pakets) = create_layers(installer, options.layer_mode)?;
+
+    let config_paket = match &options.config_paket {
+        Some(name) => Some(
+            ordered_pakets
+                .iter()
+                .find(|c| &c.package.name == name)
+                .ok_or_else(|| PaketError::NotInstalled(name.clone()))?,
+        ),
+        None => ordered_pakets.last(),
+    };
+
+    let config = to_json(&image_config(config_paket, &layers))?;
+    let config_digest = sha256::calculate_sha256(&config);
+
+    let manifest = to_json(&json!({
+        "schemaVersion": 2,
+        "mediaType": MEDIA_TYPE_MANIFEST,
+        "config": {
+            "mediaType": MEDIA_TYPE_CONFIG,
+            "digest": format!("sha256:{config_digest}"),
+            "size": config.len(),
+        },
+        "layers": layers.iter().map(|l| json!({
+            "mediaType": MEDIA_TYPE_LAYER,
+            "digest": format!("sha256:{}", l.digest),
+            "size": l.blob.len(),
+        })).collect::<Vec<Value>>(),
+    }))?;
+    let manifest_digest = sha256::calculate_sha256(&manifest);
+
+    let tag = options
+        .tag
+        .clone()
+        .or_else(|| config_paket.map(|c| sanitize_tag(&c.package.version)))
+        .unwrap_or_else(|| "latest".to_string());
+
+    let index = to_json(&json!({
+        "schemaVersion": 2,
+        "mediaType": MEDIA_TYPE_INDEX,
+        "manifests": [{
+            "mediaType": MEDIA_TYPE_MANIFEST,
+            "digest": format!("sha256:{manifest_digest}"),
+            "size": manifest.len(),
+            "annotations": { "org.opencontainers.image.ref.name": tag },
+        }],
+    }))?;
+
+    let mut tar_builder = tar::Builder::new(File::create(output)?);
+    append_bytes(
+        &mut tar_builder,
+        br#"{"imageLayoutVersion":"1.0.0"}"#,
+        "oci-layout",
+    )?;
+    append_bytes(&mut tar_builder, &index, "index.json")?;
+    append_blob(&mut tar_builder, &manifest_digest, &manifest)?;
+    append_blob(&mut tar_builder, &config_digest, &config)?;
+    for layer in &layers {
+        append_blob(&mut tar_builder, &layer.digest, &layer.blob)?;
+    }
+    tar_builder.into_inner()?.sync_all()?;
+
+    Ok(())
+}
+
+/// Bootstrap the pakets into a temporary root folder and export it as an OCI image layout tarball.
+///
+/// See: `bootstrap::bootstrap` and `export_oci_image`
+pub fn export_oci_image_from_pakets(
+    paket_path_list: &[PathBuf],
+    output: &Path,
+    options: &OciExportOptions,
+) -> Result<()> {
+    // Removed when it's dropped
+    let root_dir = tempfile::tempdir()?;
+
+    let installer = bootstrap::bootstrap(root_dir.path(), paket_path_list)?;
+    export_oci_image(&installer, output, options)
+}
+
+/// Make a valid tag of an OCI reference from a version string.
+///
+/// Tags can only have `[a-zA-Z0-9_.-]` characters, can't start with `.` or `-` and are at most 128 characters long.
+/// Other characters are replaced with `_`, like the `+` of the semver build metadata.
+///
+/// Example:
+/// ```rust
+/// use libpaket::oci::sanitize_tag;
+///
+/// assert_eq!("1.2.0-rc.1_build.5", sanitize_tag("1.2.0-rc.1+build.5"));
+/// assert_eq!("_beta", sanitize_tag("-beta"));
+/// ```
+pub fn sanitize_tag(version: &str) -> String {
+    version
+        .chars()
+        .take(128)
+        .enumerate()
+        .map(|(i, c)| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => c,
+            '.' | '-' if i > 0 => c,
+            _ => '_',
+        })
+        .collect()
+}
@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+use crate::install::Installer;
+use crate::toml_structs::files_toml::FilesIndex;
+use crate::toml_structs::installed_toml::InstalledFile;
+use crate::toml_structs::paket_toml;
+use crate::{PaketError, Result};
+
+/// Reverse index file in the state folder.
+const FILES_INDEX: &str = "files.toml";
+
+/// An installed file and the pakets which own it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FileOwner {
+    pub path: String,
+    pub pakets: Vec<String>,
+}
+
+fn index_path(installer: &Installer) -> PathBuf {
+    installer.get_state_dir().join(FILES_INDEX)
+}
+
+/// Create the reverse index from the installed records of all pakets.
+pub fn rebuild_index(installer: &Installer) -> Result<FilesIndex> {
+    let mut index = FilesIndex::default();
+
+    for config in installer.installed_pakets()? {
+        let files = installer.installed_files(&config.package.name)?;
+        index.add_paket(&config.package.name, files.iter().map(|f| f.path.as_str()));
+    }
+
+    write_index(installer, &index)?;
+
+    Ok(index)
+}
+
+/// Read the reverse index. It is created from the installed records if it doesn't exist.
+pub fn read_index(installer: &Installer) -> Result<FilesIndex> {
+    let index_path = index_path(installer);
+
+    match index_path.exists() {
+        true => paket_toml::read_toml_file(&index_path),
+        false => rebuild_index(installer),
+    }
+}
+
+fn write_index(installer: &Installer, index: &FilesIndex) -> Result<()> {
+    let content =
+        toml::to_string(index).map_err(|e| PaketError::TomlSerializeError(e.to_string()))?;
+
+    fs::create_dir_all(installer.get_state_dir())?;
+    fs::write(index_path(installer), content)?;
+
+    Ok(())
+}
+
+/// Add the installed files of a paket to the reverse index. Files of its older version are removed.
+pub(crate) fn add_paket_files(
+    installer: &Installer,
+    paket_name: &str,
+    files: &[InstalledFile],
+) -> Result<()> {
+    let mut index = read_index(installer)?;
+    index.remove_paket(paket_name);
+    index.add_paket(paket_name, files.iter().map(|f| f.path.as_str()));
+
+    write_index(installer, &index)
+}
+
+/// Remove the files of a paket from the reverse index.
+pub(crate) fn remove_paket_files(installer: &Installer, paket_name: &str) -> Result<()> {
+    let mut index = read_index(installer)?;
+    index.remove_paket(paket_name);
+
+    write_index(installer, &index)
+}
+
+fn is_glob(s: &str) -> bool {
+    s.contains(['*', '?', '['])
+}
+
+/// Find the pakets which own the installed file.
+///
+/// `path` can be:
+/// - An absolute path in the installed system: `/usr/bin/helloworld`
+/// - A relative path to the current folder: `./helloworld`
+/// - A glob pattern: `/usr/share/icons/**/*.svg` (`*` doesn't match `/`, `**` matches folders)
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+///
+/// let owners = libpaket::owners::find_owners(&Installer::default(), "/usr/bin/helloworld").unwrap();
+/// assert_eq!(owners[0].pakets, vec!["helloworld"]);
+/// ```
+pub fn find_owners(installer: &Installer, path: &str) -> Result<Vec<FileOwner>> {
+    let index = read_index(installer)?;
+
+    let mut path = PathBuf::from(path);
+    if path.is_relative() {
+        // `components()` drops the `.` components: `/usr/bin/./helloworld` -> `/usr/bin/helloworld`
+        path = std::env::current_dir()?.join(path).components().collect();
+
+        // Relative paths inside another root filesystem: `--root ./rootfs` in `./rootfs/usr/bin`
+        if let Ok(root_dir) = fs::canonicalize(installer.get_root_dir()) {
+            if let Ok(stripped) = path.strip_prefix(&root_dir) {
+                path = Path::new("/").join(stripped);
+            }
+        }
+    }
+    let path_string = path.to_string_lossy().to_string();
+
+    if is_glob(&path_string) {
+        let pattern =
+            Pattern::new(&path_string).map_err(|e| PaketError::InvalidPattern(e.to_string()))?;
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        return Ok(index
+            .files
+            .iter()
+            .filter(|(p, _)| pattern.matches_with(p, options))
+            .map(|(p, pakets)| FileOwner {
+                path: p.clone(),
+                pakets: pakets.clone(),
+            })
+            .collect());
+    }
+
+    let mut candidates = vec![path_string];
+    // Symlinked folders like /bin -> /usr/bin on the running system
+    if installer.get_root_dir() == Path::new("/") {
+        if let Ok(canonical) = fs::canonicalize(&path) {
+            candidates.push(canonical.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(candidates
+        .into_iter()
+        .find(|p| !index.owners(p).is_empty())
+        .map(|p| FileOwner {
+            pakets: index.owners(&p).to_vec(),
+            path: p,
+        })
+        .into_iter()
+        .collect())
+}
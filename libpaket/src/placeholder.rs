@@ -0,0 +1,236 @@
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{PaketError, Result};
+
+/// Placeholder folders which can be used as the top level folders of a configuration paket.
+///
+/// Files under a placeholder folder are installed into the home folders of users instead of the system root.
+///
+/// Example paket folder:
+/// ```text
+/// ./
+/// ├── Paket.toml
+/// └── $USER_HOME/
+///     └── .fonts/
+///         └── hack/
+///             └── Hack-Regular.ttf  -> /home/<user>/.fonts/hack/Hack-Regular.ttf
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Placeholder {
+    /// `$USER_HOME` is the home folder of the user. (e.g. `/home/emin`)
+    UserHome,
+
+    /// `$XDG_CONFIG_HOME` of the user, the `.config` folder in the home folder by default. (e.g. `/home/emin/.config`)
+    XdgConfigHome,
+
+    /// `$XDG_DATA_HOME` of the user, the `.local/share` folder in the home folder by default. (e.g. `/home/emin/.local/share`)
+    XdgDataHome,
+}
+
+impl Placeholder {
+    /// Get the placeholder from its folder name like `$USER_HOME`.
+    pub fn from_name(name: &str) -> Option<Placeholder> {
+        match name {
+            "$USER_HOME" => Some(Placeholder::UserHome),
+            "$XDG_CONFIG_HOME" => Some(Placeholder::XdgConfigHome),
+            "$XDG_DATA_HOME" => Some(Placeholder::XdgDataHome),
+            _ => None,
+        }
+    }
+
+    /// Folder name of the placeholder like `$USER_HOME`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Placeholder::UserHome => "$USER_HOME",
+            Placeholder::XdgConfigHome => "$XDG_CONFIG_HOME",
+            Placeholder::XdgDataHome => "$XDG_DATA_HOME",
+        }
+    }
+
+    /// Default path of the placeholder relative to a home folder. See: `path_in_home`
+    pub fn relative_to_home(&self) -> &'static Path {
+        match self {
+            Placeholder::UserHome => Path::new(""),
+            Placeholder::XdgConfigHome => Path::new(".config"),
+            Placeholder::XdgDataHome => Path::new(".local/share"),
+        }
+    }
+
+    /// Path of the placeholder in a home folder.
+    ///
+    /// For the home folder of the current user (`$HOME`), `$XDG_CONFIG_HOME` and `$XDG_DATA_HOME` are read from the
+    /// environment. The default `.config` and `.local/share` folders are used only when they are not set.
+    pub fn path_in_home(&self, home: &Path) -> PathBuf {
+        let variable = match self {
+            Placeholder::UserHome => None,
+            Placeholder::XdgConfigHome => Some("XDG_CONFIG_HOME"),
+            Placeholder::XdgDataHome => Some("XDG_DATA_HOME"),
+        };
+
+        let is_current_home = std::env::var_os("HOME").is_some_and(|h| Path::new(&h) == home);
+        if let Some(folder) = variable
+            .filter(|_| is_current_home)
+            .and_then(std::env::var_os)
+            .map(PathBuf::from)
+        {
+            // Relative paths are invalid in the XDG Base Directory Specification and must be ignored
+            if folder.is_absolute() {
+                return folder;
+            }
+        }
+
+        home.join(self.relative_to_home())
+    }
+}
+
+/// Split a path inside a paket like `$USER_HOME/.fonts/a.ttf` to its placeholder and the rest of the path: `.fonts/a.ttf`
+///
+/// Returns `None` if the path doesn't start with a placeholder.
+pub fn split_placeholder(path: &Path) -> Option<(Placeholder, PathBuf)> {
+    let mut components = path.components().skip_while(|c| *c == Component::CurDir);
+
+    let placeholder = match components.next() {
+        Some(Component::Normal(first)) => Placeholder::from_name(first.to_str()?)?,
+        _ => return None,
+    };
+
+    Some((placeholder, components.collect()))
+}
+
+/// Which home folders the files under placeholder folders are installed into.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum UserHomePolicy {
+    /// Home folder of the user who runs paket. (`$SUDO_USER` if paket runs with sudo)
+    #[default]
+    InvokingUser,
+
+    /// Home folders of every normal user (UID >= 1000) and `/etc/skel` for users created later.
+    AllUsers,
+
+    /// Only `/etc/skel`, so only users created later will have the files.
+    Skel,
+}
+
+/// A user entry in `/etc/passwd`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct User {
+    pub name: String,
+    pub uid: u32,
+    pub gid: u32,
+    pub home: PathBuf,
+}
+
+/// A home folder to install the placeholder files into.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HomeTarget {
+    /// Owner of the home folder. `None` for `/etc/skel`.
+    pub user: Option<User>,
+    pub home: PathBuf,
+}
+
+const MIN_NORMAL_USER_UID: u32 = 1000;
+const MAX_NORMAL_USER_UID: u32 = 60000;
+
+/// Parse `/etc/passwd` formatted content.
+pub fn parse_passwd(content: &str) -> Vec<User> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            // name:password:uid:gid:gecos:home:shell
+            let fields: Vec<&str> = line.split(':').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+
+            Some(User {
+                name: fields[0].to_string(),
+                uid: fields[2].parse().ok()?,
+                gid: fields[3].parse().ok()?,
+                home: PathBuf::from(fields[5]),
+            })
+        })
+        .collect()
+}
+
+fn read_passwd(passwd_path: &Path) -> Result<Vec<User>> {
+    if !passwd_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(parse_passwd(&fs::read_to_string(passwd_path)?))
+}
+
+/// Find the user who runs paket. `$SUDO_USER` is preferred over `$USER` to find the real user behind sudo.
+fn invoking_user(users: &[User]) -> Result<User> {
+    let username = std::env::var("SUDO_USER")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default();
+
+    let home = std::env::var("HOME").unwrap_or_default();
+
+    if let Some(user) = users
+        .iter()
+        .find(|u| u.name == username)
+        .or_else(|| users.iter().find(|u| u.home == Path::new(&home)))
+    {
+        return Ok(user.clone());
+    }
+
+    // User is not in passwd (e.g. a container), fallback to $HOME and its owner
+    if home.is_empty() {
+        return Err(PaketError::UserNotFound(username));
+    }
+
+    let metadata = fs::metadata(&home)?;
+
+    Ok(User {
+        name: username,
+        uid: metadata.uid(),
+        gid: metadata.gid(),
+        home: PathBuf::from(home),
+    })
+}
+
+/// List the home folders which placeholder files will be installed into by the given policy.
+///
+/// `root` is the root of the system which contains `etc/passwd` and the home folders.
+pub fn home_targets(root: &Path, policy: UserHomePolicy) -> Result<Vec<HomeTarget>> {
+    let users = read_passwd(&root.join("etc/passwd"))?;
+    let skel = HomeTarget {
+        user: None,
+        home: PathBuf::from("/etc/skel"),
+    };
+
+    let targets = match policy {
+        UserHomePolicy::InvokingUser => {
+            let user = invoking_user(&users)?;
+            vec![HomeTarget {
+                home: user.home.clone(),
+                user: Some(user),
+            }]
+        }
+        UserHomePolicy::AllUsers => {
+            let mut targets: Vec<HomeTarget> = users
+                .into_iter()
+                .filter(|u| (MIN_NORMAL_USER_UID..MAX_NORMAL_USER_UID).contains(&u.uid))
+                .filter(|u| {
+                    root.join(u.home.strip_prefix("/").unwrap_or(&u.home))
+                        .is_dir()
+                })
+                .map(|u| HomeTarget {
+                    home: u.home.clone(),
+                    user: Some(u),
+                })
+                .collect();
+            targets.push(skel);
+
+            targets
+        }
+        UserHomePolicy::Skel => vec![skel],
+    };
+
+    Ok(targets)
+}
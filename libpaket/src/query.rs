@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::fs;
+
+use glob::Pattern;
+use semver::{Version, VersionReq};
+
+use crate::install::Installer;
+use crate::remote::{self, RemotePaket};
+use crate::repos;
+use crate::toml_structs::installed_toml::InstalledInfo;
+use crate::toml_structs::paket_toml::{Config, PackageType};
+use crate::{PaketError, Result};
+
+/// An installed paket with its installation information.
+#[derive(Debug)]
+pub struct InstalledPaket {
+    /// `Paket.toml` of the installed paket.
+    pub config: Config,
+
+    /// `Installed.toml` of the installed paket.
+    pub info: InstalledInfo,
+
+    /// Total size of the installed files in bytes. Files which are removed after the installation are not counted.
+    pub size: u64,
+}
+
+impl InstalledPaket {
+    /// Check if a higher version of the paket is in `available_versions`.
+    pub fn is_upgradable(&self, available_versions: &HashMap<String, Version>) -> bool {
+        match (
+            available_versions.get(&self.config.package.name),
+            Version::parse(&self.config.package.version),
+        ) {
+            (Some(available), Ok(installed)) => *available > installed,
+            _ => false,
+        }
+    }
+}
+
+/// Filters of the installed pakets to list. All pakets are listed with the default filter.
+#[derive(Debug, Default)]
+pub struct ListFilter {
+    /// Only the pakets with this type.
+    pub package_type: Option<PackageType>,
+
+    /// Only the pakets whose names match. Glob patterns like `lib*` match the whole name, other texts match a part of the name.
+    pub name_pattern: Option<String>,
+
+    /// `Some(true)`: only the pakets installed as dependencies, `Some(false)`: only the explicitly installed pakets.
+    pub dependency: Option<bool>,
+
+    /// Only the pakets which have a higher version in these available versions (e.g. from the repositories).
+    pub upgradable: Option<HashMap<String, Version>>,
+}
+
+/// Read an installed paket with its installation information and size.
+pub fn installed_paket(installer: &Installer, config: Config) -> Result<InstalledPaket> {
+    let info = installer.installed_info(&config.package.name)?;
+
+    let mut size = 0;
+    for file in info.files.iter().filter(|f| f.sha256.is_some()) {
+        if let Ok(metadata) = fs::symlink_metadata(installer.host_path(&file.path)) {
+            size += metadata.len();
+        }
+    }
+
+    Ok(InstalledPaket { config, info, size })
+}
+
+/// Find an installed paket by its name.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+///
+/// let paket = libpaket::query::find_installed_paket(&Installer::default(), "helloworld").unwrap();
+/// println!("{} files, {} bytes", paket.info.files.len(), paket.size);
+/// ```
+pub fn find_installed_paket(installer: &Installer, paket_name: &str) -> Result<InstalledPaket> {
+    let config = installer
+        .installed_pakets()?
+        .into_iter()
+        .find(|c| c.package.name == paket_name)
+        .ok_or_else(|| PaketError::NotInstalled(paket_name.to_string()))?;
+
+    installed_paket(installer, config)
+}
+
+/// Find a paket in the downloaded indexes of the repositories. The paket which `paket install` would choose is
+/// returned. See: `remote::find_paket`
+pub fn find_repository_paket(installer: &Installer, paket_name: &str) -> Result<RemotePaket> {
+    let repositories = repos::stored_repositories(installer)?;
+
+    remote::find_paket(&repositories, paket_name, &VersionReq::STAR)
+        .ok_or_else(|| PaketError::PaketNotFound(paket_name.to_string()))
+}
+
+/// List the installed pakets which match the filter, sorted by their names.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+/// use libpaket::query::{list_pakets, ListFilter};
+/// use libpaket::toml_structs::paket_toml::PackageType;
+///
+/// let filter = ListFilter {
+///     package_type: Some(PackageType::Library),
+///     name_pattern: Some(String::from("libgtk*")),
+///     ..Default::default()
+/// };
+/// for paket in list_pakets(&Installer::default(), &filter).unwrap() {
+///     println!("{} {} {}", paket.config.package.name, paket.config.package.version, paket.size);
+/// }
+/// ```
+pub fn list_pakets(installer: &Installer, filter: &ListFilter) -> Result<Vec<InstalledPaket>> {
+    let name_matches: Box<dyn Fn(&str) -> bool> = match &filter.name_pattern {
+        Some(p) if p.contains(['*', '?', '[']) => {
+            let pattern = Pattern::new(p).map_err(|e| PaketError::InvalidPattern(e.to_string()))?;
+            Box::new(move |name| pattern.matches(name))
+        }
+        Some(p) => Box::new(move |name| name.contains(p.as_str())),
+        None => Box::new(|_| true),
+    };
+
+    let mut pakets = Vec::new();
+    for config in installer.installed_pakets()? {
+        if filter
+            .package_type
+            .is_some_and(|t| t != config.package.package_type)
+            || !name_matches(&config.package.name)
+        {
+            continue;
+        }
+
+        let paket = installed_paket(installer, config)?;
+
+        if filter
+            .dependency
+            .is_some_and(|d| d != paket.info.dependency)
+            || filter
+                .upgradable
+                .as_ref()
+                .is_some_and(|available| !paket.is_upgradable(available))
+        {
+            continue;
+        }
+
+        pakets.push(paket);
+    }
+
+    pakets.sort_by(|a, b| a.config.package.name.cmp(&b.config.package.name));
+
+    Ok(pakets)
+}
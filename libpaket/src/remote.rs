@@ -0,0 +1,436 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use semver::{Version, VersionReq};
+
+use crate::cache;
+use crate::repository;
+use crate::sha256;
+use crate::signature;
+use crate::toml_structs::index_toml::{IndexEntry, RepositoryIndex};
+use crate::toml_structs::paket_toml;
+use crate::toml_structs::repos_toml::RepositoryConfig;
+use crate::{PaketError, Result};
+
+/// Folder of the downloaded .paket files relative to the state folder. See: `Installer::get_state_dir`
+pub const CACHE_FOLDER: &str = "cache";
+
+/// A repository served by `paket-server` or a repository folder on the disk, with its downloaded index.
+#[derive(Debug, Clone)]
+pub struct RemoteRepository {
+    name: String,
+    url: String,
+    index: RepositoryIndex,
+
+    /// Architectures of the pakets to use, with the `any` pakets.
+    architectures: Vec<String>,
+
+    /// Pakets which are taken only from this repository. See: `RepositoryConfig::pinned`
+    pinned: Vec<String>,
+}
+
+/// A paket in a repository to download.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RemotePaket {
+    /// URL of the .paket file.
+    pub url: String,
+
+    /// Name of the repository which has the paket.
+    pub repository: String,
+
+    /// Entry of the paket in the repository index.
+    pub entry: IndexEntry,
+}
+
+impl RemoteRepository {
+    /// Download the index of a repository. The URL is used as the name of the repository.
+    ///
+    /// `url` is the repository root with `http://`, `https://` or `file://` like `http://localhost:8080` or `file:///srv/repository`.
+    ///
+    /// The index is checked like the repositories in /etc/paket/repos.d: it must be signed with the secret key of
+    /// `trusted_key`, unless `allow_unsigned` is set. See: `RepositoryConfig::trusted_key`
+    pub fn open(
+        url: &str,
+        trusted_key: Option<&str>,
+        allow_unsigned: bool,
+    ) -> Result<RemoteRepository> {
+        let url = url.trim_end_matches('/');
+        signature::check_trust(url, trusted_key, allow_unsigned)?;
+
+        let compressed_index = fetch(&format!("{url}/{}", repository::INDEX_FILE))?;
+        if let Some(trusted_key) = trusted_key {
+            let signature = fetch(&format!("{url}/{}", repository::SIGNATURE_FILE))?;
+            let signature = String::from_utf8_lossy(&signature);
+            signature::verify(&compressed_index, signature.trim(), trusted_key)?;
+        }
+
+        Ok(RemoteRepository::from_index(
+            url,
+            repository::parse_index(&compressed_index)?,
+        ))
+    }
+
+    /// Create a repository from an index which is already downloaded. The URL is used as the name of the repository.
+    pub fn from_index(url: &str, index: RepositoryIndex) -> RemoteRepository {
+        let url = url.trim_end_matches('/').to_string();
+
+        RemoteRepository {
+            name: url.clone(),
+            url,
+            index,
+            architectures: vec![paket_toml::host_architecture().to_string()],
+            pinned: Vec::new(),
+        }
+    }
+
+    /// Create a configured repository from its downloaded index. See: `repos::stored_repositories`
+    pub fn from_config(
+        name: &str,
+        config: &RepositoryConfig,
+        index: RepositoryIndex,
+    ) -> RemoteRepository {
+        RemoteRepository {
+            name: name.to_string(),
+            url: config.get_channel_url(),
+            index,
+            architectures: config
+                .architectures
+                .clone()
+                .unwrap_or_else(|| vec![paket_toml::host_architecture().to_string()]),
+            pinned: config.pinned.clone(),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn get_index(&self) -> &RepositoryIndex {
+        &self.index
+    }
+
+    /// Check if the paket is taken only from this repository.
+    pub fn is_pinned(&self, paket_name: &str) -> bool {
+        self.pinned.iter().any(|p| p == paket_name)
+    }
+
+    /// Find the highest version of a paket which matches the version requirement and the architectures of the repository.
+    pub fn find(&self, paket_name: &str, requirement: &VersionReq) -> Option<RemotePaket> {
+        self.index
+            .pakets
+            .iter()
+            .filter(|p| p.name == paket_name)
+            .filter(|p| {
+                p.architectures
+                    .iter()
+                    .any(|a| a == "any" || self.architectures.contains(a))
+            })
+            .filter_map(|p| Some((Version::parse(&p.version).ok()?, p)))
+            .filter(|(version, _)| requirement.matches(version))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, entry)| RemotePaket {
+                url: format!("{}/{}", self.url, entry.filename),
+                repository: self.name.clone(),
+                entry: entry.clone(),
+            })
+    }
+}
+
+/// Find a paket in the repositories which are ordered by their priorities.
+///
+/// If a repository pins the paket, it is taken only from the pinning repositories. Otherwise it is taken from the
+/// first repository which has a matching version.
+pub fn find_paket(
+    repositories: &[RemoteRepository],
+    paket_name: &str,
+    requirement: &VersionReq,
+) -> Option<RemotePaket> {
+    let is_pinned = repositories.iter().any(|r| r.is_pinned(paket_name));
+
+    repositories
+        .iter()
+        .filter(|r| !is_pinned || r.is_pinned(paket_name))
+        .find_map(|r| r.find(paket_name, requirement))
+}
+
+/// Versions of the pakets in the repositories which `resolve_pakets` chooses. See: `find_paket`
+pub fn available_versions(repositories: &[RemoteRepository]) -> HashMap<String, Version> {
+    let mut versions = HashMap::new();
+    for entry in repositories.iter().flat_map(|r| &r.get_index().pakets) {
+        if versions.contains_key(&entry.name) {
+            continue;
+        }
+
+        let paket = find_paket(repositories, &entry.name, &VersionReq::STAR);
+
+        if let Some(version) = paket.and_then(|p| Version::parse(&p.entry.version).ok()) {
+            versions.insert(entry.name.clone(), version);
+        }
+    }
+
+    versions
+}
+
+/// A file downloaded by `fetch_if_changed`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FetchedFile {
+    pub content: Vec<u8>,
+
+    /// `ETag` header of the response.
+    pub etag: Option<String>,
+
+    /// `Last-Modified` header of the response.
+    pub last_modified: Option<String>,
+}
+
+/// Read a file from an `http://`, `https://` or `file://` URL.
+pub fn fetch(url: &str) -> Result<Vec<u8>> {
+    Ok(fetch_if_changed(url, None, None)?
+        .map(|f| f.content)
+        .unwrap_or_default())
+}
+
+/// Read a file from a URL if it is changed since the response with the `etag` or `last_modified` headers.
+///
+/// Returns `None` if the server answers `304 Not Modified`. `file://` URLs are always read.
+pub fn fetch_if_changed(
+    url: &str,
+    etag: Option<&str>,
+    last_modified: Option<&str>,
+) -> Result<Option<FetchedFile>> {
+    let download_error =
+        |e: &dyn std::fmt::Display| PaketError::DownloadError(format!("{url}: {e}"));
+
+    let mut content = Vec::new();
+    if let Some(path) = url.strip_prefix("file://") {
+        File::open(path)
+            .and_then(|mut f| f.read_to_end(&mut content))
+            .map_err(|e| download_error(&e))?;
+
+        return Ok(Some(FetchedFile {
+            content,
+            etag: None,
+            last_modified: None,
+        }));
+    }
+
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(download_error(
+            &"Only http://, https:// and file:// URLs are supported",
+        ));
+    }
+
+    let mut request = ureq::get(url);
+    if let Some(etag) = etag {
+        request = request.set("If-None-Match", etag);
+    }
+    if let Some(last_modified) = last_modified {
+        request = request.set("If-Modified-Since", last_modified);
+    }
+
+    let response = request.call().map_err(|e| download_error(&e))?;
+    if response.status() == 304 {
+        return Ok(None);
+    }
+
+    let etag = response.header("ETag").map(String::from);
+    let last_modified = response.header("Last-Modified").map(String::from);
+    response
+        .into_reader()
+        .read_to_end(&mut content)
+        .map_err(|e| download_error(&e))?;
+
+    Ok(Some(FetchedFile {
+        content,
+        etag,
+        last_modified,
+    }))
+}
+
+/// Find the pakets to install by their names in the repositories, with their dependencies which are not installed.
+///
+/// Repositories are chosen by `find_paket`. `installed` is the installed pakets (name -> version).
+///
+/// Recommended pakets which are not installed are added too if `install_recommends` is true. They are skipped if they
+/// are not found in the repositories.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+/// use libpaket::remote::*;
+///
+/// let installer = Installer::default();
+/// let repositories = vec![RemoteRepository::open("http://localhost:8080", None, true).unwrap()];
+///
+/// let pakets = resolve_pakets(&repositories, &[String::from("vlc")], &installer.installed_versions().unwrap(), true).unwrap();
+/// for paket in pakets {
+///     println!("{} {} -> {}", paket.entry.name, paket.entry.version, paket.url);
+/// }
+/// ```
+pub fn resolve_pakets(
+    repositories: &[RemoteRepository],
+    paket_names: &[String],
+    installed: &HashMap<String, Version>,
+    install_recommends: bool,
+) -> Result<Vec<RemotePaket>> {
+    let mut pakets: Vec<RemotePaket> = Vec::new();
+    for name in paket_names {
+        if pakets.iter().any(|p| &p.entry.name == name) {
+            continue;
+        }
+
+        let paket = find_paket(repositories, name, &VersionReq::STAR)
+            .ok_or_else(|| PaketError::PaketNotFound(name.clone()))?;
+        pakets.push(paket);
+    }
+
+    resolve_dependencies(repositories, pakets, installed, install_recommends)
+}
+
+/// Add the dependencies of the chosen pakets which are not installed, like `resolve_pakets`.
+///
+/// The chosen pakets are kept at the start of the returned list.
+pub fn resolve_dependencies(
+    repositories: &[RemoteRepository],
+    mut pakets: Vec<RemotePaket>,
+    installed: &HashMap<String, Version>,
+    install_recommends: bool,
+) -> Result<Vec<RemotePaket>> {
+    let find = |name: &str, requirement: &VersionReq| find_paket(repositories, name, requirement);
+
+    // Dependencies of the dependencies are added to the end of the list and checked too
+    let mut i = 0;
+    while i < pakets.len() {
+        let entry = pakets[i].entry.clone();
+        let recommends = entry.recommends.iter().filter(|_| install_recommends);
+
+        for (is_recommended, (name, requirement)) in entry
+            .dependencies
+            .iter()
+            .map(|d| (false, d))
+            .chain(recommends.map(|r| (true, r)))
+        {
+            let requirement = VersionReq::parse(requirement).map_err(|_| {
+                PaketError::DependencyError(format!(
+                    "Invalid version requirement of {name} in {}: {requirement}",
+                    entry.name
+                ))
+            })?;
+
+            let selected = pakets.iter().find(|p| &p.entry.name == name);
+
+            // Recommended pakets are only installed if they don't conflict with anything
+            if is_recommended {
+                if selected.is_none() && !installed.contains_key(name) {
+                    pakets.extend(find(name, &requirement));
+                }
+                continue;
+            }
+
+            if let Some(selected) = selected {
+                let version = Version::parse(&selected.entry.version);
+                if !version.is_ok_and(|v| requirement.matches(&v)) {
+                    return Err(PaketError::DependencyError(format!(
+                        "{name} ({requirement}) required by {} conflicts with {name} {}",
+                        entry.name, selected.entry.version
+                    )));
+                }
+                continue;
+            }
+
+            if installed.get(name).is_some_and(|v| requirement.matches(v)) {
+                continue;
+            }
+
+            let dependency = find(name, &requirement).ok_or_else(|| {
+                PaketError::DependencyError(format!(
+                    "{name} ({requirement}) required by {} is not found in the repositories",
+                    entry.name
+                ))
+            })?;
+            pakets.push(dependency);
+        }
+
+        i += 1;
+    }
+
+    Ok(pakets)
+}
+
+/// Download a paket into the cache folder and check its SHA256 with the repository index.
+///
+/// Files are kept by their names, versions and SHA256s (See: `cache::paket_path`), so a .paket file which is
+/// already in the cache folder isn't downloaded again.
+///
+/// Returns the path of the downloaded .paket file.
+pub fn download_paket(paket: &RemotePaket, cache_dir: &Path) -> Result<PathBuf> {
+    let paket_path = cache::paket_path(cache_dir, &paket.entry)?;
+
+    if let Ok(cached) = File::open(&paket_path) {
+        if sha256::calculate_sha256_of_reader(cached)? == paket.entry.sha256 {
+            return Ok(paket_path);
+        }
+    }
+
+    let content = fetch(&paket.url)?;
+    let calculated = sha256::calculate_sha256(&content);
+    if calculated != paket.entry.sha256 {
+        return Err(PaketError::ChecksumMismatch(format!(
+            "{}: expected {}, calculated {calculated}",
+            paket.url, paket.entry.sha256
+        )));
+    }
+
+    // Write to a temporary file first not to leave a broken .paket file in the cache
+    if let Some(folder) = paket_path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    let temporary_path = paket_path.with_extension("paket.part");
+    fs::write(&temporary_path, content)?;
+    fs::rename(&temporary_path, &paket_path)?;
+
+    Ok(paket_path)
+}
+
+/// Download the pakets into the cache folder like `download_paket`, `parallel_downloads` of them at the same time.
+///
+/// Returns the paths of the downloaded .paket files in the same order with the pakets.
+pub fn download_pakets(
+    pakets: &[RemotePaket],
+    cache_dir: &Path,
+    parallel_downloads: usize,
+) -> Result<Vec<PathBuf>> {
+    let mut paket_paths = Vec::with_capacity(pakets.len());
+
+    for chunk in pakets.chunks(parallel_downloads.max(1)) {
+        let results: Vec<Result<PathBuf>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|paket| scope.spawn(move || download_paket(paket, cache_dir)))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|h| {
+                    h.join().unwrap_or_else(|_| {
+                        Err(PaketError::DownloadError(String::from(
+                            "Download thread panicked",
+                        )))
+                    })
+                })
+                .collect()
+        });
+
+        for result in results {
+            paket_paths.push(result?);
+        }
+    }
+
+    Ok(paket_paths)
+}
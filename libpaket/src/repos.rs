@@ -0,0 +1,241 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::atomic::{write_replacing, write_temporary};
+use crate::install::Installer;
+use crate::remote::{self, RemoteRepository};
+use crate::repository::{self, INDEX_FILE, SIGNATURE_FILE};
+use crate::sha256;
+use crate::signature;
+use crate::toml_structs::index_toml::RepositoryIndex;
+use crate::toml_structs::paket_toml;
+use crate::toml_structs::repos_toml::{RepositoryConfig, RepositoryState};
+use crate::{PaketError, Result};
+
+/// Folder of the repository configurations relative to the root folder. See: `repos_toml::RepositoryConfig`
+pub const REPOS_CONFIG_FOLDER: &str = "etc/paket/repos.d";
+
+/// Folder of the downloaded repository indexes relative to the state folder. See: `Installer::get_state_dir`
+pub const REPOS_FOLDER: &str = "repos";
+
+/// Download state of an index relative to its repository folder. See: `repos_toml::RepositoryState`
+const STATE_FILE: &str = "Repository.toml";
+
+/// A repository in `/etc/paket/repos.d`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfiguredRepository {
+    /// File name of the configuration without `.toml`.
+    pub name: String,
+
+    pub config: RepositoryConfig,
+}
+
+/// Result of `update_repository`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum UpdateStatus {
+    /// A new index is downloaded.
+    Updated,
+
+    /// The index is the same as the downloaded one.
+    NotModified,
+}
+
+/// Read the repository configurations in `<root>/etc/paket/repos.d/*.toml`, sorted by their names. Disabled repositories are included.
+pub fn read_repositories(installer: &Installer) -> Result<Vec<ConfiguredRepository>> {
+    let config_dir = installer.get_root_dir().join(REPOS_CONFIG_FOLDER);
+    if !config_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut repositories = Vec::new();
+    for entry in fs::read_dir(&config_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|e| e == "toml") {
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+
+            repositories.push(ConfiguredRepository {
+                name,
+                config: paket_toml::read_toml_file(&path)?,
+            });
+        }
+    }
+
+    repositories.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(repositories)
+}
+
+/// Folder of the downloaded index of a repository: `<state_dir>/repos/<name>`
+fn repository_dir(installer: &Installer, repository: &ConfiguredRepository) -> PathBuf {
+    installer
+        .get_state_dir()
+        .join(REPOS_FOLDER)
+        .join(&repository.name)
+}
+
+/// Read the downloaded index of a repository. Its signature is checked with the trusted key of the repository.
+///
+/// Repositories without a trusted key are read only if `allow_unsigned` is set. See: `signature::check_trust`
+fn read_stored_index(repository: &ConfiguredRepository, folder: &Path) -> Result<RepositoryIndex> {
+    let config = &repository.config;
+    signature::check_trust(
+        &repository.name,
+        config.trusted_key.as_deref(),
+        config.allow_unsigned,
+    )?;
+
+    let compressed_index = fs::read(folder.join(INDEX_FILE))?;
+
+    if let Some(trusted_key) = &config.trusted_key {
+        let signature = fs::read_to_string(folder.join(SIGNATURE_FILE))?;
+        signature::verify(&compressed_index, signature.trim(), trusted_key)?;
+    }
+
+    repository::parse_index(&compressed_index)
+}
+
+/// Download the index of a repository into `<state_dir>/repos/<name>` if it is changed.
+///
+/// The index isn't downloaded again if the server answers `304 Not Modified` to the `ETag` and `Last-Modified` of
+/// the previous download, and it isn't replaced if its SHA256 is the same.
+///
+/// The new index must be a valid index, signed with the trusted key of the repository. Repositories without a trusted
+/// key are updated only if `allow_unsigned` is set. Otherwise the error is returned and the last downloaded index is kept.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+/// use libpaket::repos::*;
+///
+/// let installer = Installer::default();
+/// for repository in read_repositories(&installer).unwrap().iter().filter(|r| r.config.enabled) {
+///     match update_repository(&installer, repository) {
+///         Ok(status) => println!("{}: {status:?}", repository.name),
+///         Err(e) => println!("{}: {e}", repository.name),
+///     }
+/// }
+/// ```
+pub fn update_repository(
+    installer: &Installer,
+    repository: &ConfiguredRepository,
+) -> Result<UpdateStatus> {
+    let config = &repository.config;
+    let url = config.get_channel_url();
+    let folder = repository_dir(installer, repository);
+
+    signature::check_trust(
+        &repository.name,
+        config.trusted_key.as_deref(),
+        config.allow_unsigned,
+    )?;
+
+    // The previous download is used only if it's still valid for the configuration
+    let state: Option<RepositoryState> = paket_toml::read_toml_file(&folder.join(STATE_FILE))
+        .ok()
+        .filter(|s: &RepositoryState| s.url == url)
+        .filter(|_| read_stored_index(repository, &folder).is_ok());
+
+    let fetched = remote::fetch_if_changed(
+        &format!("{url}/{INDEX_FILE}"),
+        state.as_ref().and_then(|s| s.etag.as_deref()),
+        state.as_ref().and_then(|s| s.last_modified.as_deref()),
+    )?;
+
+    let fetched = match fetched {
+        Some(f) => f,
+        None => return Ok(UpdateStatus::NotModified),
+    };
+
+    let new_state = RepositoryState {
+        url: url.clone(),
+        etag: fetched.etag,
+        last_modified: fetched.last_modified,
+        sha256: sha256::calculate_sha256(&fetched.content),
+        update_time: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs()),
+    };
+    let state_content =
+        toml::to_string(&new_state).map_err(|e| PaketError::TomlSerializeError(e.to_string()))?;
+
+    if state.is_some_and(|s| s.sha256 == new_state.sha256) {
+        write_replacing(&folder.join(STATE_FILE), state_content.as_bytes())?;
+        return Ok(UpdateStatus::NotModified);
+    }
+
+    // Check the new index before replacing the last good one
+    repository::parse_index(&fetched.content)?;
+
+    let signature = match &config.trusted_key {
+        Some(trusted_key) => {
+            let signature = remote::fetch(&format!("{url}/{SIGNATURE_FILE}"))?;
+            let signature = String::from_utf8_lossy(&signature).trim().to_string();
+            signature::verify(&fetched.content, &signature, trusted_key)?;
+
+            Some(signature)
+        }
+        None => None,
+    };
+
+    // Every file is written before any of them is replaced. The index is replaced after its signature, then the
+    // download state is saved. An interrupted update leaves an index which isn't verified or isn't the one in the
+    // state, so it's downloaded again by the next update.
+    fs::create_dir_all(&folder)?;
+    let signature_path = folder.join(SIGNATURE_FILE);
+    let new_signature = match signature {
+        Some(signature) => Some(write_temporary(&signature_path, signature.as_bytes())?),
+        None => None,
+    };
+    let new_state = write_temporary(&folder.join(STATE_FILE), state_content.as_bytes())?;
+    let new_index = write_temporary(&folder.join(INDEX_FILE), &fetched.content)?;
+
+    match new_signature {
+        Some(new_signature) => fs::rename(new_signature, &signature_path)?,
+        None if signature_path.exists() => fs::remove_file(&signature_path)?,
+        None => (),
+    }
+    fs::rename(new_index, folder.join(INDEX_FILE))?;
+    fs::rename(new_state, folder.join(STATE_FILE))?;
+
+    Ok(UpdateStatus::Updated)
+}
+
+/// Read the downloaded indexes of the enabled repositories to install and search the pakets.
+///
+/// Repositories are ordered by their priorities, the highest first. See: `remote::find_paket`
+///
+/// Returns an error if a repository is not downloaded with `update_repository` yet.
+pub fn stored_repositories(installer: &Installer) -> Result<Vec<RemoteRepository>> {
+    let mut repositories: Vec<ConfiguredRepository> = read_repositories(installer)?
+        .into_iter()
+        .filter(|r| r.config.enabled)
+        .collect();
+
+    // Sort is stable, repositories with the same priority stay sorted by their names
+    repositories.sort_by_key(|r| Reverse(r.config.priority));
+
+    repositories
+        .into_iter()
+        .map(|repository| {
+            let folder = repository_dir(installer, &repository);
+            if !folder.join(INDEX_FILE).exists() {
+                return Err(PaketError::RepositoryNotUpdated(repository.name));
+            }
+
+            let index = read_stored_index(&repository, &folder)?;
+
+            Ok(RemoteRepository::from_config(
+                &repository.name,
+                &repository.config,
+                index,
+            ))
+        })
+        .collect()
+}
@@ -0,0 +1,241 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use semver::Version;
+
+use crate::archive::PaketArchive;
+use crate::atomic::write_temporary;
+use crate::dependency;
+use crate::sha256;
+use crate::signature;
+use crate::toml_structs::index_toml::{IndexEntry, RepositoryIndex};
+use crate::{PaketError, Result};
+
+/// Compressed index of the .paket files relative to the repository root. See: `index_toml::RepositoryIndex`
+pub const INDEX_FILE: &str = "index.toml.gz";
+
+/// Signature of the compressed index relative to the repository root. See: `signature::sign`
+pub const SIGNATURE_FILE: &str = "index.toml.gz.sig";
+
+/// Folder of the .paket files relative to the repository root.
+pub const POOL_FOLDER: &str = "pool";
+
+/// Changes of the repository index after it is updated. Lists the .paket files relative to the repository root.
+#[derive(Debug, PartialEq, Default)]
+pub struct IndexChanges {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+    pub unchanged: usize,
+}
+
+impl IndexChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Create the index entry of a .paket file in a repository.
+///
+/// Repository layout:
+/// ```text
+/// <repository>/
+/// ├── index.toml.gz
+/// ├── index.toml.gz.sig
+/// └── pool/
+///     ├── helloworld_0.1.0.paket
+///     └── font-hack_3.3.0.paket
+/// ```
+pub fn index_entry(repository_dir: &Path, paket_path: &Path) -> Result<IndexEntry> {
+    let filename = paket_path
+        .strip_prefix(repository_dir)
+        .map_err(|_| PaketError::InvalidPaketPath(paket_path.to_string_lossy().to_string()))?
+        .to_string_lossy()
+        .to_string();
+
+    let archive = PaketArchive::open(paket_path)?;
+    let config = archive.get_config();
+
+    Ok(IndexEntry {
+        name: config.package.name.clone(),
+        version: config.package.version.clone(),
+        package_type: config.package.package_type,
+        architectures: config.package.architectures.clone(),
+        description: config.package.description.clone(),
+        keywords: config.package.keywords.clone().unwrap_or_default(),
+        categories: config.package.categories.clone().unwrap_or_default(),
+        dependencies: dependency::required_dependencies(config)?
+            .into_iter()
+            .map(|(name, requirement)| (name, requirement.to_string()))
+            .collect(),
+        recommends: dependency::recommended_dependencies(config)?
+            .into_iter()
+            .map(|(name, requirement)| (name, requirement.to_string()))
+            .collect(),
+        filename,
+        size: fs::metadata(paket_path)?.len(),
+        sha256: sha256::calculate_sha256_of_reader(File::open(paket_path)?)?,
+    })
+}
+
+/// Parse the compressed index of a repository.
+pub fn parse_index(compressed_index: &[u8]) -> Result<RepositoryIndex> {
+    let mut content = String::new();
+    GzDecoder::new(compressed_index).read_to_string(&mut content)?;
+
+    toml::from_str(&content).map_err(|e| PaketError::TomlParseError(e.message().to_string()))
+}
+
+/// Read the compressed index of a repository.
+pub fn read_index(repository_dir: &Path) -> Result<RepositoryIndex> {
+    parse_index(&fs::read(repository_dir.join(INDEX_FILE))?)
+}
+
+/// Write the compressed index of a repository. The index is signed if a secret key is given.
+///
+/// Each file is written into a temporary file first and renamed over the old one, so the clients never download a
+/// partly written file. The index and its signature are not replaced together though: a client which downloads them
+/// between the two renames gets the new signature with the old index, and fails to verify it until it downloads the
+/// index again.
+pub fn write_index(
+    repository_dir: &Path,
+    index: &RepositoryIndex,
+    secret_key: Option<&str>,
+) -> Result<()> {
+    let content =
+        toml::to_string(index).map_err(|e| PaketError::TomlSerializeError(e.to_string()))?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(content.as_bytes())?;
+    let compressed_index = encoder.finish()?;
+
+    let index_path = repository_dir.join(INDEX_FILE);
+    let signature_path = repository_dir.join(SIGNATURE_FILE);
+
+    let signature = secret_key
+        .map(|key| signature::sign(&compressed_index, key))
+        .transpose()?;
+    let temporary_index = write_temporary(&index_path, &compressed_index)?;
+
+    match signature {
+        Some(signature) => {
+            let temporary_signature = write_temporary(&signature_path, signature.as_bytes())
+                .inspect_err(|_| {
+                    let _ = fs::remove_file(&temporary_index);
+                })?;
+            fs::rename(temporary_signature, &signature_path)?;
+        }
+        None => {
+            if signature_path.exists() {
+                fs::remove_file(&signature_path)?;
+            }
+        }
+    }
+    fs::rename(temporary_index, index_path)?;
+
+    Ok(())
+}
+
+/// Find the .paket files in a folder and its sub folders.
+fn find_paket_files(folder: &Path, paket_files: &mut Vec<PathBuf>) -> Result<()> {
+    for dir_entry in fs::read_dir(folder)? {
+        let path = dir_entry?.path();
+
+        if path.is_dir() {
+            find_paket_files(&path, paket_files)?;
+        } else if path.extension().is_some_and(|e| e == "paket") {
+            paket_files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Create or update the index of the .paket files in a repository folder and its sub folders. (e.g. `pool/`)
+///
+/// Only the new and changed .paket files are read, entries of the other files are kept from the old index.
+/// A file is changed if its size is different or it's modified after the old index.
+///
+/// The index is signed if a secret key is given. See: `signature::generate_key_pair`
+///
+/// Example:
+/// ```rust,no_run
+/// use std::path::Path;
+///
+/// let changes = libpaket::repository::update_index(Path::new("./repository"), None).unwrap();
+/// println!("{} new pakets", changes.added.len());
+/// ```
+pub fn update_index(repository_dir: &Path, secret_key: Option<&str>) -> Result<IndexChanges> {
+    let index_path = repository_dir.join(INDEX_FILE);
+    let (old_index, index_time) = match index_path.exists() {
+        // A broken index is created again
+        true => (
+            read_index(repository_dir).unwrap_or_default(),
+            fs::metadata(&index_path)?.modified().ok(),
+        ),
+        false => (RepositoryIndex::default(), None),
+    };
+
+    let mut paket_files = Vec::new();
+    find_paket_files(repository_dir, &mut paket_files)?;
+    paket_files.sort();
+
+    let mut changes = IndexChanges::default();
+    let mut index = RepositoryIndex::default();
+
+    for paket_path in paket_files {
+        let metadata = fs::metadata(&paket_path)?;
+        let filename = paket_path
+            .strip_prefix(repository_dir)
+            .unwrap_or(&paket_path)
+            .to_string_lossy()
+            .to_string();
+
+        let old_entry = old_index.pakets.iter().find(|p| p.filename == filename);
+        let is_changed = |old_size: u64| {
+            old_size != metadata.len()
+                || match (metadata.modified().ok(), index_time) {
+                    (Some(modified), Some(index_time)) => modified > index_time,
+                    _ => true,
+                }
+        };
+
+        match old_entry {
+            Some(old) if !is_changed(old.size) => {
+                changes.unchanged += 1;
+                index.pakets.push(old.clone());
+            }
+            Some(_) => {
+                changes.updated.push(filename);
+                index.pakets.push(index_entry(repository_dir, &paket_path)?);
+            }
+            None => {
+                changes.added.push(filename);
+                index.pakets.push(index_entry(repository_dir, &paket_path)?);
+            }
+        }
+    }
+
+    changes.removed = old_index
+        .pakets
+        .iter()
+        .filter(|old| !index.pakets.iter().any(|p| p.filename == old.filename))
+        .map(|old| old.filename.clone())
+        .collect();
+
+    index.pakets.sort_by(|a, b| {
+        (&a.name, Version::parse(&a.version).ok()).cmp(&(&b.name, Version::parse(&b.version).ok()))
+    });
+
+    // Keep the index as is if nothing is changed, clients can use their cached copies.
+    let is_signed = repository_dir.join(SIGNATURE_FILE).exists();
+    if !changes.is_empty() || index_time.is_none() || is_signed != secret_key.is_some() {
+        write_index(repository_dir, &index, secret_key)?;
+    }
+
+    Ok(changes)
+}
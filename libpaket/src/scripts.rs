@@ -0,0 +1,128 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+
+use crate::toml_structs::paket_toml::ScriptPhase;
+use crate::{PaketError, Result};
+
+/// Maintainer scripts of a paket. See: `paket_toml::Scripts`
+///
+/// Scripts are stored as `scripts/<phase>` in the .paket file and in the installed record folder of the paket.
+#[derive(Debug, Default, Clone)]
+pub struct PaketScripts {
+    scripts: Vec<(ScriptPhase, Vec<u8>)>,
+}
+
+/// Information passed to the maintainer scripts as environment variables.
+#[derive(Debug)]
+pub struct ScriptEnvironment<'a> {
+    /// `PAKET_ROOT`
+    pub root_dir: &'a Path,
+
+    /// Folder to write the script into before running it.
+    pub work_dir: &'a Path,
+
+    /// `PAKET_NAME`
+    pub paket_name: &'a str,
+
+    /// `PAKET_OLD_VERSION`
+    pub old_version: Option<&'a str>,
+
+    /// `PAKET_NEW_VERSION`
+    pub new_version: Option<&'a str>,
+
+    /// `LANG`, inherited from paket if not given.
+    pub language: Option<&'a str>,
+}
+
+impl PaketScripts {
+    /// Scripts read from the `scripts/<phase>` files inside a .paket file. See: `archive::PaketArchive`
+    pub(crate) fn new(scripts: Vec<(ScriptPhase, Vec<u8>)>) -> PaketScripts {
+        PaketScripts { scripts }
+    }
+
+    /// Read the scripts saved by `write_to_folder`. Returns no scripts if the folder doesn't exist.
+    pub fn from_folder(folder: &Path) -> Result<PaketScripts> {
+        let mut scripts = Vec::new();
+
+        for phase in ScriptPhase::all() {
+            let script_path = folder.join(phase.name());
+            if script_path.is_file() {
+                scripts.push((*phase, fs::read(script_path)?));
+            }
+        }
+
+        Ok(PaketScripts { scripts })
+    }
+
+    /// Save the scripts as `<folder>/<phase>` to run them after the .paket file is gone. (e.g. `pre_remove`)
+    pub fn write_to_folder(&self, folder: &Path) -> Result<()> {
+        if self.scripts.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(folder)?;
+        for (phase, content) in &self.scripts {
+            let script_path = folder.join(phase.name());
+            fs::write(&script_path, content)?;
+            fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, phase: ScriptPhase) -> Option<&[u8]> {
+        self.scripts
+            .iter()
+            .find(|(p, _)| *p == phase)
+            .map(|(_, content)| content.as_slice())
+    }
+
+    /// Run the script of the phase if the paket has it.
+    ///
+    /// Returns `PaketError::ScriptError` if the script can't be run or exits with a non-zero status.
+    pub fn run(&self, phase: ScriptPhase, env: &ScriptEnvironment) -> Result<()> {
+        let content = match self.get(phase) {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+
+        fs::create_dir_all(env.work_dir)?;
+        let script_path =
+            fs::canonicalize(env.work_dir)?.join(format!("{}_{}", env.paket_name, phase.name()));
+        fs::write(&script_path, content)?;
+        fs::set_permissions(&script_path, fs::Permissions::from_mode(0o755))?;
+
+        let root_dir = fs::canonicalize(env.root_dir)?;
+        let mut command = Command::new(&script_path);
+        if let Some(language) = env.language {
+            command.env("LANG", language);
+        }
+
+        let status = command
+            .current_dir(&root_dir)
+            .env("PAKET_ROOT", &root_dir)
+            .env("PAKET_NAME", env.paket_name)
+            .env("PAKET_PHASE", phase.name())
+            .env("PAKET_OLD_VERSION", env.old_version.unwrap_or_default())
+            .env("PAKET_NEW_VERSION", env.new_version.unwrap_or_default())
+            .status();
+
+        fs::remove_file(&script_path)?;
+
+        match status {
+            Ok(s) if s.success() => Ok(()),
+            Ok(s) => Err(PaketError::ScriptError(format!(
+                "{} {} exited with {s}",
+                env.paket_name,
+                phase.name()
+            ))),
+            Err(e) => Err(PaketError::ScriptError(format!(
+                "{} {}: {e}",
+                env.paket_name,
+                phase.name()
+            ))),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use semver::VersionReq;
+
+use crate::install::Installer;
+use crate::remote::{self, RemoteRepository};
+use crate::toml_structs::paket_toml::PackageType;
+use crate::Result;
+
+/// How well a paket matches a search term. Higher is better.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum MatchRank {
+    Description,
+    Category,
+    Keyword,
+    NameContains,
+    NamePrefix,
+    ExactName,
+}
+
+/// A paket found by `search_pakets`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SearchResult {
+    pub name: String,
+
+    /// Version in the repositories, or the installed version if the paket is not in the repositories.
+    pub version: String,
+
+    pub package_type: PackageType,
+    pub description: String,
+
+    /// Rank of the weakest matching search term.
+    pub rank: MatchRank,
+
+    pub installed_version: Option<String>,
+
+    /// Name of the repository which has the paket.
+    pub repository: Option<String>,
+}
+
+/// Filters of the search. Repository and installed pakets of all types are searched with the default options.
+#[derive(Debug, Default)]
+pub struct SearchOptions {
+    /// Only the pakets with this type.
+    pub package_type: Option<PackageType>,
+
+    /// Only the installed pakets.
+    pub installed_only: bool,
+}
+
+/// Searchable fields of a paket.
+struct Candidate {
+    result: SearchResult,
+    keywords: Vec<String>,
+    categories: Vec<String>,
+}
+
+/// Lowercase a text for case insensitive search with Turkish letters.
+///
+/// `İ`, `I`, `ı` and `i` are all folded to `i`, so `ılık`, `ILIK` and `ilik` are the same,
+/// and `İstanbul` is not lowercased to `i̇stanbul` (with a combining dot) like `str::to_lowercase` does.
+pub fn fold_case(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| match c {
+            'İ' | 'I' | 'ı' => 'i'.to_lowercase(),
+            c => c.to_lowercase(),
+        })
+        .collect()
+}
+
+/// Rank how a folded search term matches a paket.
+fn match_rank(term: &str, candidate: &Candidate) -> Option<MatchRank> {
+    let name = fold_case(&candidate.result.name);
+    let any_matches = |texts: &[String]| texts.iter().any(|t| fold_case(t).contains(term));
+
+    if name == term {
+        Some(MatchRank::ExactName)
+    } else if name.starts_with(term) {
+        Some(MatchRank::NamePrefix)
+    } else if name.contains(term) {
+        Some(MatchRank::NameContains)
+    } else if any_matches(&candidate.keywords) {
+        Some(MatchRank::Keyword)
+    } else if any_matches(&candidate.categories) {
+        Some(MatchRank::Category)
+    } else if fold_case(&candidate.result.description).contains(term) {
+        Some(MatchRank::Description)
+    } else {
+        None
+    }
+}
+
+/// Search the pakets in the repositories and the installed pakets by their names, keywords, categories and descriptions.
+///
+/// Every term must match the paket. Results are sorted by their ranks (exact name > name prefix > name > keyword > category > description), then by their names.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+/// use libpaket::remote::RemoteRepository;
+/// use libpaket::search::{search_pakets, SearchOptions};
+///
+/// let repositories = vec![RemoteRepository::open("http://localhost:8080", None, true).unwrap()];
+/// let terms = vec![String::from("video")];
+///
+/// for paket in search_pakets(&Installer::default(), &repositories, &terms, &SearchOptions::default()).unwrap() {
+///     println!("{} {} {:?}", paket.name, paket.version, paket.rank);
+/// }
+/// ```
+pub fn search_pakets(
+    installer: &Installer,
+    repositories: &[RemoteRepository],
+    terms: &[String],
+    options: &SearchOptions,
+) -> Result<Vec<SearchResult>> {
+    let mut candidates: BTreeMap<String, Candidate> = BTreeMap::new();
+
+    for config in installer.installed_pakets()? {
+        let package = config.package;
+        candidates.insert(
+            package.name.clone(),
+            Candidate {
+                result: SearchResult {
+                    installed_version: Some(package.version.clone()),
+                    name: package.name,
+                    version: package.version,
+                    package_type: package.package_type,
+                    description: package.description,
+                    rank: MatchRank::ExactName,
+                    repository: None,
+                },
+                keywords: package.keywords.unwrap_or_default(),
+                categories: package.categories.unwrap_or_default(),
+            },
+        );
+    }
+
+    if !options.installed_only {
+        let mut names: Vec<&str> = repositories
+            .iter()
+            .flat_map(|r| r.get_index().pakets.iter().map(|p| p.name.as_str()))
+            .collect();
+        names.sort();
+        names.dedup();
+
+        for name in names {
+            // The repository which `remote::resolve_pakets` chooses
+            let Some(paket) = remote::find_paket(repositories, name, &VersionReq::STAR) else {
+                continue;
+            };
+            let (repository, paket) = (paket.repository, paket.entry);
+
+            let installed_version = candidates
+                .remove(name)
+                .and_then(|c| c.result.installed_version);
+            candidates.insert(
+                paket.name.clone(),
+                Candidate {
+                    result: SearchResult {
+                        name: paket.name,
+                        version: paket.version,
+                        package_type: paket.package_type,
+                        description: paket.description,
+                        rank: MatchRank::ExactName,
+                        installed_version,
+                        repository: Some(repository),
+                    },
+                    keywords: paket.keywords,
+                    categories: paket.categories,
+                },
+            );
+        }
+    }
+
+    let terms: Vec<String> = terms.iter().map(|t| fold_case(t)).collect();
+
+    let mut results = Vec::new();
+    for mut candidate in candidates.into_values() {
+        if options
+            .package_type
+            .is_some_and(|t| t != candidate.result.package_type)
+            || (options.installed_only && candidate.result.installed_version.is_none())
+        {
+            continue;
+        }
+
+        let ranks: Option<Vec<MatchRank>> =
+            terms.iter().map(|t| match_rank(t, &candidate)).collect();
+
+        if let Some(rank) = ranks.and_then(|r| r.into_iter().min()) {
+            candidate.result.rank = rank;
+            results.push(candidate.result);
+        }
+    }
+
+    // Candidates are sorted by their names already, the sort is stable
+    results.sort_by_key(|r| Reverse(r.rank));
+
+    Ok(results)
+}
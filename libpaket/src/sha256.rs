@@ -1,3 +1,5 @@
+use std::io::{self, Read};
+
 use sha2::{Digest, Sha256};
 
 /// Calculate SHA256 of the given bytes.
@@ -14,12 +16,28 @@ pub fn calculate_sha256(input: &[u8]) -> String {
 
     hasher.update(input);
 
-    let hash_result = hasher.finalize();
+    to_hex_string(&hasher.finalize())
+}
+
+/// Calculate SHA256 of all bytes of a reader without reading them into the memory at once.
+///
+/// Example:
+/// ```rust
+/// let sha256sum = libpaket::sha256::calculate_sha256_of_reader("Selam".as_bytes()).unwrap();
+///
+/// assert_eq!(sha256sum, "26fb1ab0ca8483866f03ca66e2018b0685f3e1e84caca77b3f5643ae799d9eb4");
+/// ```
+pub fn calculate_sha256_of_reader(mut reader: impl Read) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+
+    io::copy(&mut reader, &mut hasher)?;
 
-    let hash_hex_string = hash_result
+    Ok(to_hex_string(&hasher.finalize()))
+}
+
+fn to_hex_string(hash_result: &[u8]) -> String {
+    hash_result
         .iter()
         .map(|b| format!("{:02x}", b))
-        .collect::<String>();
-
-    hash_hex_string
+        .collect::<String>()
 }
@@ -0,0 +1,102 @@
+use std::fs::File;
+use std::io::Read;
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+use crate::color::{RESET, YELLOW};
+use crate::{PaketError, Result};
+
+/// Create a new Ed25519 key pair to sign repository indexes. Returns `(secret_key, public_key)` as hex strings.
+///
+/// Example:
+/// ```rust
+/// use libpaket::signature::*;
+///
+/// let (secret_key, public_key) = generate_key_pair().unwrap();
+/// let signature = sign(b"index", &secret_key).unwrap();
+///
+/// assert!(verify(b"index", &signature, &public_key).is_ok());
+/// assert!(verify(b"changed index", &signature, &public_key).is_err());
+/// ```
+pub fn generate_key_pair() -> Result<(String, String)> {
+    let mut secret = [0u8; 32];
+    File::open("/dev/urandom")?.read_exact(&mut secret)?;
+
+    let signing_key = SigningKey::from_bytes(&secret);
+
+    Ok((
+        to_hex(&signing_key.to_bytes()),
+        to_hex(&signing_key.verifying_key().to_bytes()),
+    ))
+}
+
+/// Public key of a secret key as a hex string.
+pub fn public_key_of(secret_key: &str) -> Result<String> {
+    Ok(to_hex(&signing_key(secret_key)?.verifying_key().to_bytes()))
+}
+
+/// Sign the data with a secret key. Returns the signature as a hex string.
+pub fn sign(data: &[u8], secret_key: &str) -> Result<String> {
+    Ok(to_hex(&signing_key(secret_key)?.sign(data).to_bytes()))
+}
+
+/// Check if the signature of the data is signed by the secret key of the public key.
+pub fn verify(data: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let invalid = |s: &str| PaketError::InvalidSignature(s.to_string());
+
+    let public_key: [u8; 32] = from_hex(public_key)
+        .and_then(|k| k.try_into().ok())
+        .ok_or_else(|| invalid("invalid public key"))?;
+    let public_key =
+        VerifyingKey::from_bytes(&public_key).map_err(|_| invalid("invalid public key"))?;
+
+    let signature: [u8; 64] = from_hex(signature)
+        .and_then(|s| s.try_into().ok())
+        .ok_or_else(|| invalid("invalid signature"))?;
+
+    public_key
+        .verify(data, &Signature::from_bytes(&signature))
+        .map_err(|_| invalid("signature doesn't match the public key"))
+}
+
+/// Check if a repository can be used with its trusted key.
+///
+/// Indexes must be signed. A repository without a trusted key is used only if it's explicitly allowed, with a warning.
+pub(crate) fn check_trust(
+    repository: &str,
+    trusted_key: Option<&str>,
+    allow_unsigned: bool,
+) -> Result<()> {
+    match (trusted_key, allow_unsigned) {
+        (Some(_), _) => Ok(()),
+        (None, true) => {
+            eprintln!("{YELLOW}Warning:{RESET} Index of {repository} is not signed, it can't be verified.");
+            Ok(())
+        }
+        (None, false) => Err(PaketError::UnsignedRepository(repository.to_string())),
+    }
+}
+
+fn signing_key(secret_key: &str) -> Result<SigningKey> {
+    let secret: [u8; 32] = from_hex(secret_key)
+        .and_then(|k| k.try_into().ok())
+        .ok_or_else(|| PaketError::InvalidSignature(String::from("invalid secret key")))?;
+
+    Ok(SigningKey::from_bytes(&secret))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    let s = s.trim();
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
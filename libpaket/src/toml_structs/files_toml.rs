@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Reverse index of the installed files to the pakets which installed them.
+///
+/// Stored at: /var/lib/paket/files.toml
+///
+/// Example **files.toml**:
+/// ```toml
+/// [files]
+/// "/usr/bin/helloworld" = ["helloworld"]
+/// "/usr/share/icons/hicolor/scalable/apps/helloworld.svg" = ["helloworld"]
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct FilesIndex {
+    /// Absolute path of the installed file -> names of the pakets which own it.
+    #[serde(default)]
+    pub files: BTreeMap<String, Vec<String>>,
+}
+
+impl FilesIndex {
+    /// Add the files of a paket.
+    pub fn add_paket<'a>(&mut self, paket_name: &str, paths: impl IntoIterator<Item = &'a str>) {
+        for path in paths {
+            let owners = self.files.entry(path.to_string()).or_default();
+
+            if !owners.iter().any(|o| o == paket_name) {
+                owners.push(paket_name.to_string());
+            }
+        }
+    }
+
+    /// Remove all files of a paket.
+    pub fn remove_paket(&mut self, paket_name: &str) {
+        self.files.retain(|_, owners| {
+            owners.retain(|o| o != paket_name);
+            !owners.is_empty()
+        });
+    }
+
+    /// Names of the pakets which own the file.
+    pub fn owners(&self, path: &str) -> &[String] {
+        self.files
+            .get(path)
+            .map(|o| o.as_slice())
+            .unwrap_or_default()
+    }
+}
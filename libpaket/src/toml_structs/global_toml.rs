@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// When the output is colored.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Only if the output is a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    pub fn from_name(name: &str) -> Option<ColorMode> {
+        match name {
+            "auto" => Some(ColorMode::Auto),
+            "always" => Some(ColorMode::Always),
+            "never" => Some(ColorMode::Never),
+            _ => None,
+        }
+    }
+}
+
+/// Global configuration of paket. All fields are optional.
+///
+/// Stored at: /etc/paket/paket.toml
+///
+/// Example **paket.toml**:
+/// ```toml
+/// root = "/"
+/// cache_dir = "/var/cache/paket"
+/// max_cache_size = "2G"
+/// parallel_downloads = 4
+/// install_recommends = false
+/// color = "never"
+/// language = "tr_TR.UTF-8"
+/// ```
+///
+/// See `config::load` for the environment variables which override the fields.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(default)]
+pub struct GlobalConfig {
+    /// Root folder of the system to install the pakets into. (Default: `/`)
+    pub root: PathBuf,
+
+    /// Folder of the downloaded .paket files. (Default: `<state_dir>/cache`) See: `remote::CACHE_FOLDER`
+    pub cache_dir: Option<PathBuf>,
+
+    /// Size limit of the cache folder like `500M` or `2G`. The oldest downloads are removed after the installations
+    /// to fit the cache into this size. (Default: no limit) See: `cache::parse_size`
+    pub max_cache_size: Option<String>,
+
+    /// How many .paket files are downloaded at the same time. (Default: 4)
+    pub parallel_downloads: usize,
+
+    /// Install the recommended pakets of the installed pakets too. (Default: true) See: `paket_toml::Dependencies::recommended`
+    pub install_recommends: bool,
+
+    /// When the output is colored. (Default: `auto`)
+    pub color: ColorMode,
+
+    /// `LANG` of the maintainer scripts and the triggers. The environment of paket is used if not given.
+    pub language: Option<String>,
+}
+
+impl Default for GlobalConfig {
+    fn default() -> Self {
+        GlobalConfig {
+            root: PathBuf::from("/"),
+            cache_dir: None,
+            max_cache_size: None,
+            parallel_downloads: 4,
+            install_recommends: true,
+            color: ColorMode::Auto,
+            language: None,
+        }
+    }
+}
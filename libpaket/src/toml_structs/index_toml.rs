@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use super::paket_toml::PackageType;
+
+/// A .paket file in a repository.
+///
+/// Example usage in **index.toml**:
+/// ```toml
+/// [[pakets]]
+/// name = "helloworld"
+/// version = "0.1.0"
+/// type = "application"
+/// architectures = ["amd64"]
+/// description = "Simple hello world program"
+/// keywords = ["hello", "example"]
+/// categories = ["Education"]
+/// filename = "pool/helloworld_0.1.0.paket"
+/// size = 70656
+/// sha256 = "d2a84f4b8b650937ec8f73cd8be2c74add5a911ba64df27458ed8229da804a26"
+///
+/// [pakets.dependencies]
+/// python3 = "^3.11"
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct IndexEntry {
+    pub name: String,
+    pub version: String,
+
+    #[serde(rename = "type")]
+    pub package_type: PackageType,
+
+    pub architectures: Vec<String>,
+    pub description: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<String>,
+
+    /// Required pakets with their version requirements. See: `dependency::required_dependencies`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, String>,
+
+    /// Recommended pakets with their version requirements. See: `dependency::recommended_dependencies`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub recommends: BTreeMap<String, String>,
+
+    /// Path of the .paket file relative to the repository root.
+    pub filename: String,
+
+    /// Size of the .paket file in bytes.
+    pub size: u64,
+
+    /// SHA256 of the .paket file.
+    pub sha256: String,
+}
+
+/// Represents the `index.toml` of a repository which lists its .paket files.
+///
+/// Stored compressed at: <repository>/index.toml.gz
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default, Clone)]
+pub struct RepositoryIndex {
+    #[serde(default)]
+    pub pakets: Vec<IndexEntry>,
+}
+
+impl RepositoryIndex {
+    /// Find the highest version of a paket by its name.
+    pub fn find(&self, paket_name: &str) -> Option<&IndexEntry> {
+        self.pakets
+            .iter()
+            .filter(|p| p.name == paket_name)
+            .max_by_key(|p| Version::parse(&p.version).ok())
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+/// A file installed to the system by a paket.
+///
+/// Example usage in **Installed.toml**:
+/// ```toml
+/// [[files]]
+/// path = "/home/emin/.fonts/hack/Hack-Bold.ttf"
+/// user = "emin"
+/// sha256 = "9b1d5e4c7e5f1b0c2a6f9f6f8b1b9d0e3c4a7b2e1f0d9c8b7a6f5e4d3c2b1a09"
+/// mode = 420 # 0o644
+/// uid = 1000
+/// gid = 1000
+///
+/// [[files]]
+/// path = "/etc/myapp/myapp.conf"
+/// sha256 = "26fb1ab0ca8483866f03ca66e2018b0685f3e1e84caca77b3f5643ae799d9eb4"
+/// mode = 420 # 0o644
+/// uid = 0
+/// gid = 0
+/// config = true
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct InstalledFile {
+    /// Absolute path of the installed file.
+    pub path: String,
+
+    /// Owner of the home folder if the file is installed from a placeholder folder like `$USER_HOME`.
+    ///
+    /// `None` for system wide files.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// SHA256 of the installed file. `None` for folders and symlinks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// Permission bits of the installed file like `0o755`. `None` for folders and symlinks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+
+    /// Owner user id of the installed file. `None` for folders and symlinks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+
+    /// Owner group id of the installed file. `None` for folders and symlinks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+
+    /// Configuration file which is kept if it's modified locally. See: `paket_toml::Package::config_files`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub config: bool,
+
+    /// Configuration file which was modified locally and kept, the version in the paket is saved as `<file>.paketnew`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub modified: bool,
+}
+
+/// Represents the `Installed.toml` file stored next to the `Paket.toml` of an installed paket.
+///
+/// Stored at: /var/lib/paket/installed/<type>/<name>_<version>/Installed.toml
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct InstalledInfo {
+    /// Files installed by the paket.
+    #[serde(default)]
+    pub files: Vec<InstalledFile>,
+
+    /// The paket is installed as a dependency of another paket, not explicitly by the user.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dependency: bool,
+
+    /// Installation time of the paket as unix time in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_time: Option<u64>,
+}
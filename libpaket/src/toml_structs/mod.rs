@@ -1 +1,6 @@
+pub mod files_toml;
+pub mod global_toml;
+pub mod index_toml;
+pub mod installed_toml;
 pub mod paket_toml;
+pub mod repos_toml;
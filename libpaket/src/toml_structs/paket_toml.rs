@@ -2,7 +2,7 @@ use std::{fs::File, io::Read, path::Path};
 
 use crate::{PaketError, Result};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use toml;
 
 /// Example usage in **Paket.toml**:
@@ -10,7 +10,7 @@ use toml;
 /// [package]
 /// type = "application"
 /// ```
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum PackageType {
     /// an Application in binary compiled form
@@ -58,10 +58,46 @@ pub enum PackageType {
     Configuration,
 }
 
+impl PackageType {
+    /// All paket types.
+    pub fn all() -> &'static [PackageType] {
+        &[
+            PackageType::Application,
+            PackageType::Script,
+            PackageType::Library,
+            PackageType::DevelopmentLibrary,
+            PackageType::ApplicationSourceCode,
+            PackageType::LibrarySourceCode,
+            PackageType::Configuration,
+        ]
+    }
+
+    /// Name of the type in Paket.toml like `development_library`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PackageType::Application => "application",
+            PackageType::Script => "script",
+            PackageType::Library => "library",
+            PackageType::DevelopmentLibrary => "development_library",
+            PackageType::ApplicationSourceCode => "application_source_code",
+            PackageType::LibrarySourceCode => "library_source_code",
+            PackageType::Configuration => "configuration",
+        }
+    }
+
+    /// Find the paket type by its name in Paket.toml.
+    pub fn from_name(name: &str) -> Option<PackageType> {
+        PackageType::all()
+            .iter()
+            .find(|t| t.name() == name)
+            .copied()
+    }
+}
+
 /// `[package]` table in Paket.toml file
 ///
 /// Stores the information about the package like `name`, `description`, `architectures`.
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct Package {
     // ------------- Must Fields -------------
     /// Example usage in **Paket.toml**:
@@ -160,12 +196,37 @@ pub struct Package {
     /// categories = ["Game", "Education"]
     /// ```
     pub categories: Option<Vec<String>>,
+
+    /// Configuration files which the users can modify. Files under `/etc` are always configuration files.
+    ///
+    /// - On upgrade, locally modified configuration files are kept and the new version is saved as `<file>.paketnew`
+    /// - On remove, configuration files are kept unless they are purged.
+    ///
+    /// A folder path makes all files in it configuration files.
+    ///
+    /// Example usage in **Paket.toml**:
+    /// ```toml
+    /// [package]
+    /// config_files = ["/usr/share/myapp/settings.ini", "$USER_HOME/.config/myapp"]
+    /// ```
+    pub config_files: Option<Vec<String>>,
+
+    /// Pakets whose files can be overwritten by this paket. The overwritten files are owned by this paket after the installation.
+    ///
+    /// Installation fails if a file of the paket is owned by another installed paket which is not replaced.
+    ///
+    /// Example usage in **Paket.toml**:
+    /// ```toml
+    /// [package]
+    /// replaces = ["old-helloworld"]
+    /// ```
+    pub replaces: Option<Vec<String>>,
 }
 
 /// `[dependencies]` table in Paket.toml file
 ///
 /// Stores the information of dependent applications, libraries or development libraries of the package.
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct Dependencies {
     /// Application dependencies of the package
     ///
@@ -199,12 +260,24 @@ pub struct Dependencies {
     /// libgtk = "3"
     /// ```   
     pub development: Option<toml::Table>,
+
+    /// Optional pakets which are installed with the package if `install_recommends` is enabled.
+    /// See: `global_toml::GlobalConfig`
+    ///
+    /// The package is installed without them if they are not found in the repositories.
+    ///
+    /// Example usage in **Paket.toml**:
+    /// ```toml
+    /// [dependencies.recommended]
+    /// font-hack = "3"
+    /// ```
+    pub recommended: Option<toml::Table>,
 }
 
 /// `[application]` table in Paket.toml file
 ///
 /// Stores the PackageType::Application specific properties like `executable` or `icon`.
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct ApplicationInformation {
     /// Binary executable file of the application
     ///
@@ -256,7 +329,7 @@ pub struct ApplicationInformation {
 /// `[script]` table in Paket.toml file
 ///
 /// Stores the PackageType::Script specific properties like `sources`,`executable` or `icon`.
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct ScriptInformation {
     /// Main executable file in the directory in `sources` property.
     ///
@@ -315,8 +388,140 @@ pub struct ScriptInformation {
     pub desktop_file: Option<String>,
 }
 
+/// Installation phases which a maintainer script can run at.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ScriptPhase {
+    /// Before the files are extracted. Runs on both new installations and upgrades.
+    PreInstall,
+
+    /// After the files are extracted on a new installation.
+    PostInstall,
+
+    /// Before the files are removed.
+    PreRemove,
+
+    /// After the files are removed.
+    PostRemove,
+
+    /// After the files are extracted on an upgrade. Runs instead of `post_install` on upgrades.
+    PostUpgrade,
+}
+
+impl ScriptPhase {
+    /// All script phases.
+    pub fn all() -> &'static [ScriptPhase] {
+        &[
+            ScriptPhase::PreInstall,
+            ScriptPhase::PostInstall,
+            ScriptPhase::PreRemove,
+            ScriptPhase::PostRemove,
+            ScriptPhase::PostUpgrade,
+        ]
+    }
+
+    /// Name of the phase in Paket.toml like `pre_install`.
+    ///
+    /// Scripts are stored as `scripts/<name>` in the .paket file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScriptPhase::PreInstall => "pre_install",
+            ScriptPhase::PostInstall => "post_install",
+            ScriptPhase::PreRemove => "pre_remove",
+            ScriptPhase::PostRemove => "post_remove",
+            ScriptPhase::PostUpgrade => "post_upgrade",
+        }
+    }
+
+    /// Find the phase by its name in Paket.toml.
+    pub fn from_name(name: &str) -> Option<ScriptPhase> {
+        ScriptPhase::all()
+            .iter()
+            .find(|p| p.name() == name)
+            .copied()
+    }
+}
+
+/// `[scripts]` table in Paket.toml file
+///
+/// Maintainer scripts to run while installing, upgrading or removing the paket.
+///
+/// Scripts must be executable files next to Paket.toml. (A `#!` line is needed for non-binary scripts)
+///
+/// They run with these environment variables:
+/// - `PAKET_ROOT`: Root folder of the system which the paket is installed into. (e.g. `/` or `./rootfs`)
+/// - `PAKET_NAME`: Name of the paket.
+/// - `PAKET_PHASE`: `pre_install`, `post_install`, `pre_remove`, `post_remove` or `post_upgrade`
+/// - `PAKET_OLD_VERSION`: Installed version before the operation. Empty on new installations.
+/// - `PAKET_NEW_VERSION`: Installed version after the operation. Empty on removals.
+///
+/// If a script fails, the rest of the installation or removal is aborted.
+///
+/// Example usage in **Paket.toml**:
+/// ```toml
+/// [scripts]
+/// post_install = "post_install.sh"
+/// pre_remove = "pre_remove.sh"
+/// ```
+#[derive(Debug, Deserialize, PartialEq, Default, Clone)]
+pub struct Scripts {
+    pub pre_install: Option<String>,
+    pub post_install: Option<String>,
+    pub pre_remove: Option<String>,
+    pub post_remove: Option<String>,
+    pub post_upgrade: Option<String>,
+}
+
+impl Scripts {
+    /// Script file of the phase.
+    pub fn get(&self, phase: ScriptPhase) -> Option<&String> {
+        match phase {
+            ScriptPhase::PreInstall => self.pre_install.as_ref(),
+            ScriptPhase::PostInstall => self.post_install.as_ref(),
+            ScriptPhase::PreRemove => self.pre_remove.as_ref(),
+            ScriptPhase::PostRemove => self.post_remove.as_ref(),
+            ScriptPhase::PostUpgrade => self.post_upgrade.as_ref(),
+        }
+    }
+}
+
+/// `[[triggers]]` array in Paket.toml file
+///
+/// A trigger runs its command once at the end of an installation or removal if any paket in it changed a file under one of the `paths`.
+///
+/// Triggers of all installed pakets are checked, so a paket can define a trigger for the files of other pakets.
+///
+/// `command` runs with `sh -c` and these environment variables:
+/// - `PAKET_ROOT`: Root folder of the system. (e.g. `/` or `./rootfs`)
+/// - `PAKET_PREFIX`: Folder which `/usr` files are installed into. (`<root>/usr` or `~/.local` for user installations)
+///
+/// Example usage in **Paket.toml**:
+/// ```toml
+/// [[triggers]]
+/// name = "update-mime-database"
+/// paths = ["/usr/share/mime/packages"]
+/// command = "update-mime-database \"$PAKET_PREFIX/share/mime\""
+/// ```
+///
+/// Paths can start with a placeholder to match the files in home folders:
+/// ```toml
+/// [[triggers]]
+/// name = "fc-cache"
+/// paths = ["/usr/share/fonts", "$USER_HOME/.fonts"]
+/// command = "fc-cache"
+/// ```
+#[derive(Debug, Deserialize, PartialEq, Clone)]
+pub struct Trigger {
+    /// Triggers with the same name run only once.
+    pub name: String,
+
+    /// Path prefixes in the installed system.
+    pub paths: Vec<String>,
+
+    pub command: String,
+}
+
 /// Represents the whole Paket.toml file
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Deserialize, PartialEq, Clone)]
 pub struct Config {
     /// `[package]` table in Paket.toml file
     ///
@@ -337,16 +542,44 @@ pub struct Config {
     ///
     /// Stores the `PackageType::Script` specific properties like `sources`,`executable` or `icon`.
     pub script: Option<ScriptInformation>,
+
+    /// `[scripts]` table in Paket.toml file
+    ///
+    /// Maintainer scripts to run while installing, upgrading or removing the paket.
+    pub scripts: Option<Scripts>,
+
+    /// `[[triggers]]` array in Paket.toml file
+    ///
+    /// Commands to run once after the files under some paths are changed.
+    pub triggers: Option<Vec<Trigger>>,
 }
 
 impl Config {
-    pub fn get_paket_archive_name(&self) -> String {
+    /// Check if a path inside the paket is a configuration file. See: `Package::config_files`
+    ///
+    /// `paket_path` is the absolute path in the installed system (`/etc/myapp.conf`) or starts with a placeholder (`$USER_HOME/.myapprc`).
+    pub fn is_config_file(&self, paket_path: &Path) -> bool {
+        paket_path.starts_with("/etc")
+            || self
+                .package
+                .config_files
+                .iter()
+                .flatten()
+                .any(|c| paket_path.starts_with(c))
+    }
+
+    /// `<name>_<version>` of the paket. Used to name archives and installed records.
+    pub fn get_paket_full_name(&self) -> String {
         format!(
-            "{}_{}.paket",
+            "{}_{}",
             self.package.name.as_str(),
             self.package.version.as_str()
         )
     }
+
+    pub fn get_paket_archive_name(&self) -> String {
+        format!("{}.paket", self.get_paket_full_name())
+    }
 }
 
 fn is_toml_file_valid(toml_path: &Path) -> Result<()> {
@@ -372,6 +605,17 @@ fn is_toml_file_valid(toml_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Debian architecture name of the running system like `amd64`. See: `Package::architectures`
+pub fn host_architecture() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "x86" => "i386",
+        "arm" => "armhf",
+        other => other,
+    }
+}
+
 /// Read and parse a toml file to any toml struct.
 ///
 /// Example:
@@ -407,25 +651,67 @@ pub fn read_toml_file<T: serde::de::DeserializeOwned>(toml_path: &Path) -> Resul
 pub fn read_config_from_toml(toml_path: &Path) -> Result<Config> {
     let config: Config = read_toml_file(toml_path)?;
 
+    check_config(config)
+}
+
+/// Get `Config` struct from the content of a `Paket.toml` file
+///
+/// Example:
+/// ```rust
+/// use libpaket::toml_structs::paket_toml;
+///
+/// let content = r#"
+/// [package]
+/// name = "font-hack"
+/// type = "configuration"
+/// version = "3.3.0"
+/// maintainers = ["Chris Simpkins <chris@sourcefoundry.org>"]
+/// description = "A typeface designed for source code."
+/// license = "MIT"
+/// architectures = ["any"]
+/// "#;
+///
+/// let paket_config: paket_toml::Config = paket_toml::read_config_from_str(content).unwrap();
+/// assert_eq!(paket_config.package.name, "font-hack");
+/// ```
+pub fn read_config_from_str(toml_content: &str) -> Result<Config> {
+    let config: Config = toml::from_str(toml_content)
+        .map_err(|e| PaketError::TomlParseError(e.message().to_string()))?;
+
+    check_config(config)
+}
+
+fn check_config(config: Config) -> Result<Config> {
     // Check package types
     match config.package.package_type {
-        PackageType::Application => {
-            if config.application.is_none() {
-                return Err(PaketError::TomlFieldNotFound(
-                    r#"type="application" pakets must have [application] field."#.to_string(),
-                ));
-            }
+        PackageType::Application if config.application.is_none() => {
+            return Err(PaketError::TomlFieldNotFound(
+                r#"type="application" pakets must have [application] field."#.to_string(),
+            ));
         }
-        PackageType::Script => {
-            if config.script.is_none() {
-                return Err(PaketError::TomlFieldNotFound(
-                    r#"type="script" pakets must have [script] field."#.to_string(),
-                ));
-            }
+        PackageType::Script if config.script.is_none() => {
+            return Err(PaketError::TomlFieldNotFound(
+                r#"type="script" pakets must have [script] field."#.to_string(),
+            ));
         }
         PackageType::Configuration => (),
         _ => (),
     }
 
+    // Maintainer scripts must be files next to Paket.toml
+    if let Some(scripts) = &config.scripts {
+        for phase in ScriptPhase::all() {
+            match scripts.get(*phase) {
+                Some(f) if f.is_empty() || f.contains('/') => {
+                    return Err(PaketError::TomlParseError(format!(
+                        "'scripts.{}' must be a file next to Paket.toml: {f}",
+                        phase.name()
+                    )));
+                }
+                _ => (),
+            }
+        }
+    }
+
     Ok(config)
 }
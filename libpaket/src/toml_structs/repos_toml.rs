@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+/// A repository to install the pakets from. The file name without `.toml` is the name of the repository.
+///
+/// Stored at: /etc/paket/repos.d/<name>.toml
+///
+/// Example **/etc/paket/repos.d/main.toml**:
+/// ```toml
+/// url = "https://paket.pardus.org.tr"
+/// channel = "stable"
+/// trusted_key = "fd458f5f2d2e53eba4163395396f08543431ddc7edb44bd665e346b914f1b1d0"
+/// priority = 100
+/// ```
+///
+/// Example **/etc/paket/repos.d/testing.toml** to install only `vlc` from the testing channel:
+/// ```toml
+/// url = "https://paket.pardus.org.tr"
+/// channel = "testing"
+/// trusted_key = "fd458f5f2d2e53eba4163395396f08543431ddc7edb44bd665e346b914f1b1d0"
+/// priority = 10
+/// pinned = ["vlc"]
+/// ```
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct RepositoryConfig {
+    /// Repository root with `http://`, `https://` or `file://`. See: `remote::RemoteRepository::open`
+    pub url: String,
+
+    /// Disabled repositories are not updated and not used. (Default: true)
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Pakets are taken from the repository with the highest priority which has them. (Default: 0)
+    ///
+    /// Repositories with the same priority are ordered by their names.
+    #[serde(default)]
+    pub priority: i32,
+
+    /// Public key of the repository as a hex string. If given, the index must be signed with its secret key.
+    ///
+    /// Printed by `paket-server keygen`. Required unless `allow_unsigned` is set.
+    pub trusted_key: Option<String>,
+
+    /// Use the repository without a trusted key. Its index can't be verified, so a warning is printed. (Default: false)
+    #[serde(default)]
+    pub allow_unsigned: bool,
+
+    /// Architectures of the pakets to use from the repository, with the architecture independent (`any`) pakets.
+    ///
+    /// Only the architecture of the system is used if not given. See: `paket_toml::host_architecture`
+    pub architectures: Option<Vec<String>>,
+
+    /// Sub folder of the repository like `stable` or `testing`. The index is read from `<url>/<channel>/index.toml.gz`.
+    pub channel: Option<String>,
+
+    /// Pakets which are taken only from this repository, even if the other repositories have a higher priority.
+    #[serde(default)]
+    pub pinned: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RepositoryConfig {
+    /// Enabled repository with the default options.
+    pub fn new(url: impl Into<String>) -> RepositoryConfig {
+        RepositoryConfig {
+            url: url.into(),
+            enabled: true,
+            priority: 0,
+            trusted_key: None,
+            allow_unsigned: false,
+            architectures: None,
+            channel: None,
+            pinned: Vec::new(),
+        }
+    }
+
+    /// URL of the repository with its channel. The index and the .paket files are relative to this URL.
+    pub fn get_channel_url(&self) -> String {
+        let url = self.url.trim_end_matches('/');
+
+        match &self.channel {
+            Some(channel) => format!("{url}/{}", channel.trim_matches('/')),
+            None => url.to_string(),
+        }
+    }
+}
+
+/// Downloaded index of a repository, to download it again only when it is changed.
+///
+/// Stored at: /var/lib/paket/repos/<name>/Repository.toml (next to `index.toml.gz` and `index.toml.gz.sig`)
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct RepositoryState {
+    /// URL of the repository with its channel. See: `RepositoryConfig::get_channel_url`
+    pub url: String,
+
+    /// `ETag` header of the index response.
+    pub etag: Option<String>,
+
+    /// `Last-Modified` header of the index response.
+    pub last_modified: Option<String>,
+
+    /// SHA256 of the compressed index.
+    pub sha256: String,
+
+    /// Unix time of the last download of the index in seconds.
+    pub update_time: Option<u64>,
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::Result;
+
+/// Changes of an install or remove operation which are finished together.
+///
+/// Paths are backed up before they are changed for the first time, so a failed operation can be rolled back as a
+/// whole. Triggers of the changed paths run once after the operation is finished.
+pub(crate) struct Transaction {
+    /// Paths in the installed system of the changed files. (e.g. `/usr/share/fonts/a.ttf`)
+    pub changed_paths: Vec<PathBuf>,
+
+    backup_dir: PathBuf,
+
+    /// Host paths changed by the transaction with their backups, `None` if the path didn't exist.
+    journal: Vec<(PathBuf, Option<PathBuf>)>,
+    journaled: HashSet<PathBuf>,
+}
+
+impl Transaction {
+    /// Start a transaction which keeps its backups in `backup_dir`. Backups of an interrupted transaction are removed.
+    pub fn new(backup_dir: PathBuf) -> Result<Transaction> {
+        remove_path(&backup_dir)?;
+
+        Ok(Transaction {
+            changed_paths: Vec::new(),
+            backup_dir,
+            journal: Vec::new(),
+            journaled: HashSet::new(),
+        })
+    }
+
+    /// Back up a file or a folder before it's written or removed. Only the first backup of a path is kept.
+    pub fn backup(&mut self, path: &Path) -> Result<()> {
+        if !self.journaled.insert(path.to_path_buf()) {
+            return Ok(());
+        }
+
+        let backup = match fs::symlink_metadata(path) {
+            Ok(_) => {
+                fs::create_dir_all(&self.backup_dir)?;
+                let backup_path = self.backup_dir.join(self.journal.len().to_string());
+                copy_path(path, &backup_path)?;
+
+                Some(backup_path)
+            }
+            Err(_) => None,
+        };
+        self.journal.push((path.to_path_buf(), backup));
+
+        Ok(())
+    }
+
+    /// Undo the changes: new paths are removed and the backed up paths are restored, in reverse order.
+    pub fn rollback(self) -> Result<()> {
+        for (path, backup) in self.journal.iter().rev() {
+            remove_path(path)?;
+
+            if let Some(backup) = backup {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                if fs::rename(backup, path).is_err() {
+                    copy_path(backup, path)?;
+                }
+            }
+        }
+
+        remove_path(&self.backup_dir)
+    }
+
+    /// Keep the changes and remove the backups.
+    pub fn commit(&self) -> Result<()> {
+        remove_path(&self.backup_dir)
+    }
+}
+
+/// Copy a file, a symlink or a folder with its contents. Modes and owners are kept.
+fn copy_path(from: &Path, to: &Path) -> Result<()> {
+    let metadata = fs::symlink_metadata(from)?;
+
+    if metadata.is_symlink() {
+        std::os::unix::fs::symlink(fs::read_link(from)?, to)?;
+    } else if metadata.is_dir() {
+        fs::create_dir_all(to)?;
+        for entry in fs::read_dir(from)? {
+            let entry = entry?;
+            copy_path(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        fs::set_permissions(to, metadata.permissions())?;
+    } else {
+        fs::copy(from, to)?;
+    }
+
+    // Only root can give the files to other users, the files of the current user are kept as they are
+    let _ = std::os::unix::fs::lchown(to, Some(metadata.uid()), Some(metadata.gid()));
+
+    Ok(())
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => fs::remove_dir_all(path)?,
+        Ok(_) => fs::remove_file(path)?,
+        Err(_) => (),
+    }
+
+    Ok(())
+}
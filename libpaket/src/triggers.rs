@@ -0,0 +1,179 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::install::{InstallTarget, Installer};
+use crate::placeholder::{self, UserHomePolicy};
+use crate::toml_structs::paket_toml::Trigger;
+use crate::{PaketError, Result};
+
+/// Run the command if it exists. So missing tools (e.g. in a minimal root filesystem) don't fail the triggers.
+fn if_exists(tool: &str, command: &str) -> String {
+    format!("if command -v {tool} >/dev/null 2>&1; then {command}; fi")
+}
+
+/// Triggers which are always checked to refresh the system caches.
+pub fn builtin_triggers(target: InstallTarget) -> Vec<Trigger> {
+    let mut triggers = vec![
+        Trigger {
+            name: "gtk-update-icon-cache".to_string(),
+            paths: vec!["/usr/share/icons/hicolor".to_string()],
+            command: if_exists(
+                "gtk-update-icon-cache",
+                r#"gtk-update-icon-cache -q -t -f "$PAKET_PREFIX/share/icons/hicolor""#,
+            ),
+        },
+        Trigger {
+            name: "update-desktop-database".to_string(),
+            paths: vec!["/usr/share/applications".to_string()],
+            command: if_exists(
+                "update-desktop-database",
+                r#"update-desktop-database -q "$PAKET_PREFIX/share/applications""#,
+            ),
+        },
+        Trigger {
+            name: "fc-cache".to_string(),
+            paths: vec![
+                "/usr/share/fonts".to_string(),
+                "$USER_HOME/.fonts".to_string(),
+                "$XDG_DATA_HOME/fonts".to_string(),
+            ],
+            command: if_exists("fc-cache", r#"fc-cache --sysroot "$PAKET_ROOT""#),
+        },
+        Trigger {
+            name: "glib-compile-schemas".to_string(),
+            paths: vec!["/usr/share/glib-2.0/schemas".to_string()],
+            command: if_exists(
+                "glib-compile-schemas",
+                r#"glib-compile-schemas "$PAKET_PREFIX/share/glib-2.0/schemas""#,
+            ),
+        },
+    ];
+
+    // Only root can update the system library cache
+    if target == InstallTarget::System {
+        triggers.push(Trigger {
+            name: "ldconfig".to_string(),
+            paths: vec![
+                "/usr/lib".to_string(),
+                "/usr/lib64".to_string(),
+                "/etc/ld.so.conf.d".to_string(),
+            ],
+            command: if_exists("ldconfig", r#"ldconfig -r "$PAKET_ROOT""#),
+        });
+    }
+
+    triggers
+}
+
+/// Convert the trigger paths to the path prefixes in the installed system.
+///
+/// `/usr/...` paths are mapped like the installed files. (e.g. to `~/.local/...` for user installations)
+/// Placeholder paths like `$USER_HOME/.fonts` are mapped into every home folder.
+fn trigger_prefixes(installer: &Installer, trigger: &Trigger) -> Vec<PathBuf> {
+    let mut homes: Vec<PathBuf> = match installer.get_target() {
+        InstallTarget::System => {
+            placeholder::home_targets(installer.get_root_dir(), UserHomePolicy::AllUsers)
+                .unwrap_or_default()
+                .into_iter()
+                .chain(
+                    placeholder::home_targets(
+                        installer.get_root_dir(),
+                        UserHomePolicy::InvokingUser,
+                    )
+                    .unwrap_or_default(),
+                )
+                .map(|t| t.home)
+                .collect()
+        }
+        InstallTarget::User => std::env::var("HOME")
+            .map(PathBuf::from)
+            .into_iter()
+            .collect(),
+    };
+    homes.sort();
+    homes.dedup();
+
+    let mut prefixes = Vec::new();
+    for path in &trigger.paths {
+        let path = Path::new(path);
+
+        match placeholder::split_placeholder(path) {
+            Some((placeholder, rest)) => {
+                for home in &homes {
+                    prefixes.push(placeholder.path_in_home(home).join(&rest));
+                }
+            }
+            None => {
+                let relative = path.strip_prefix("/").unwrap_or(path);
+                // Paths outside of /usr are not installed in user installations
+                if let Ok(system_path) = installer.system_path(relative) {
+                    prefixes.push(system_path);
+                }
+            }
+        }
+    }
+
+    prefixes
+}
+
+/// Find the triggers which have a path prefix of the changed files.
+///
+/// Triggers with the same name are returned once.
+pub fn find_triggered<'a>(
+    installer: &Installer,
+    triggers: &'a [Trigger],
+    changed_paths: &[PathBuf],
+) -> Vec<&'a Trigger> {
+    let mut triggered: Vec<&Trigger> = Vec::new();
+
+    for trigger in triggers {
+        if triggered.iter().any(|t| t.name == trigger.name) {
+            continue;
+        }
+
+        let prefixes = trigger_prefixes(installer, trigger);
+        if changed_paths
+            .iter()
+            .any(|changed| prefixes.iter().any(|p| changed.starts_with(p)))
+        {
+            triggered.push(trigger);
+        }
+    }
+
+    triggered
+}
+
+/// Run the command of the trigger with `sh -c`.
+pub fn run_trigger(installer: &Installer, trigger: &Trigger) -> Result<()> {
+    let root_dir = fs::canonicalize(installer.get_root_dir())?;
+    let prefix = match installer.system_path(Path::new("usr")) {
+        Ok(usr) => root_dir.join(usr.strip_prefix("/").unwrap_or(&usr)),
+        Err(_) => root_dir.join("usr"),
+    };
+
+    let mut command = Command::new("sh");
+    if let Some(language) = &installer.get_options().language {
+        command.env("LANG", language);
+    }
+
+    let status = command
+        .arg("-c")
+        .arg(&trigger.command)
+        .current_dir(&root_dir)
+        .env("PAKET_ROOT", &root_dir)
+        .env("PAKET_PREFIX", prefix)
+        .status();
+
+    match status {
+        Ok(s) if s.success() => Ok(()),
+        Ok(s) => Err(PaketError::ScriptError(format!(
+            "Trigger {} exited with {s}",
+            trigger.name
+        ))),
+        Err(e) => Err(PaketError::ScriptError(format!(
+            "Trigger {}: {e}",
+            trigger.name
+        ))),
+    }
+}
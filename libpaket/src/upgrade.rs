@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::dependency;
+use crate::install::Installer;
+use crate::remote::{self, RemotePaket, RemoteRepository};
+use crate::toml_structs::paket_toml::Config;
+use crate::{PaketError, Result};
+
+/// An installed paket which has a newer version in the repositories.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PaketUpgrade {
+    pub installed_version: Version,
+    pub paket: RemotePaket,
+}
+
+/// Pakets to download and install to upgrade the system. See: `plan_upgrade`
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct UpgradePlan {
+    /// Installed pakets to upgrade, ordered by their names.
+    pub upgrades: Vec<PaketUpgrade>,
+
+    /// Dependencies (and recommended pakets) of the new versions which are not installed.
+    pub new_pakets: Vec<RemotePaket>,
+
+    /// Installed pakets which have newer versions, but are not upgraded because of the pakets which require them.
+    pub held_back: Vec<HeldBackPaket>,
+}
+
+/// An installed paket which is kept at its version, because no newer version is accepted by an installed paket.
+#[derive(Debug, PartialEq, Clone)]
+pub struct HeldBackPaket {
+    pub name: String,
+    pub installed_version: Version,
+
+    /// Newest version in the repositories.
+    pub available_version: String,
+
+    /// Installed paket which doesn't accept the newer versions, with its version requirement. e.g. `vlc-plugins (^3)`
+    pub required_by: String,
+}
+
+impl UpgradePlan {
+    /// There is nothing to download and install. Held back pakets are not counted.
+    pub fn is_empty(&self) -> bool {
+        self.upgrades.is_empty() && self.new_pakets.is_empty()
+    }
+
+    /// All pakets of the plan to download, upgrades first.
+    pub fn pakets(&self) -> impl Iterator<Item = &RemotePaket> {
+        self.upgrades
+            .iter()
+            .map(|u| &u.paket)
+            .chain(&self.new_pakets)
+    }
+}
+
+fn is_newer(paket: &RemotePaket, installed_version: &Version) -> bool {
+    Version::parse(&paket.entry.version).is_ok_and(|v| v > *installed_version)
+}
+
+/// Newest versions of the installed pakets in the repositories which are higher than the installed versions.
+///
+/// Repositories are chosen like `remote::find_paket`. Pakets which are not in the repositories are not upgraded.
+pub fn find_upgrades(
+    repositories: &[RemoteRepository],
+    installed: &HashMap<String, Version>,
+) -> Vec<PaketUpgrade> {
+    let mut upgrades: Vec<PaketUpgrade> = installed
+        .iter()
+        .filter_map(|(name, installed_version)| {
+            let paket = remote::find_paket(repositories, name, &VersionReq::STAR)?;
+
+            is_newer(&paket, installed_version).then(|| PaketUpgrade {
+                installed_version: installed_version.clone(),
+                paket,
+            })
+        })
+        .collect();
+
+    upgrades.sort_by(|a, b| a.paket.entry.name.cmp(&b.paket.entry.name));
+    upgrades
+}
+
+/// An installed paket with a newer version in the repositories, reported by `outdated_pakets`.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct OutdatedPaket {
+    pub name: String,
+    pub installed_version: String,
+    pub available_version: String,
+
+    /// Name of the repository which offers the new version.
+    pub repository: String,
+
+    /// URL of the .paket file of the new version.
+    pub url: String,
+}
+
+/// Installed pakets which have newer versions in the repositories, ordered by their names. Nothing is downloaded.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+/// use libpaket::repos::stored_repositories;
+/// use libpaket::upgrade::outdated_pakets;
+///
+/// let installer = Installer::default();
+/// let repositories = stored_repositories(&installer).unwrap();
+///
+/// for paket in outdated_pakets(&installer, &repositories).unwrap() {
+///     println!("{}: {} -> {} ({})", paket.name, paket.installed_version, paket.available_version, paket.repository);
+/// }
+/// ```
+pub fn outdated_pakets(
+    installer: &Installer,
+    repositories: &[RemoteRepository],
+) -> Result<Vec<OutdatedPaket>> {
+    let upgrades = find_upgrades(repositories, &installer.installed_versions()?);
+
+    Ok(upgrades
+        .into_iter()
+        .map(|u| OutdatedPaket {
+            name: u.paket.entry.name,
+            installed_version: u.installed_version.to_string(),
+            available_version: u.paket.entry.version,
+            repository: u.paket.repository,
+            url: u.paket.url,
+        })
+        .collect())
+}
+
+/// Plan the upgrade of all installed pakets to their newest versions in the repositories.
+///
+/// New dependencies of the new versions are resolved like `remote::resolve_pakets`.
+///
+/// If a new version breaks a dependency of an installed paket which is not upgraded, the highest version which all of
+/// the installed pakets accept is chosen. If there is no such newer version, the paket is held back and reported in
+/// `UpgradePlan::held_back`.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+/// use libpaket::repos::stored_repositories;
+/// use libpaket::upgrade::plan_upgrade;
+///
+/// let installer = Installer::default();
+/// let repositories = stored_repositories(&installer).unwrap();
+///
+/// let plan = plan_upgrade(&installer, &repositories, true).unwrap();
+/// for upgrade in plan.upgrades {
+///     println!("{}: {} -> {}", upgrade.paket.entry.name, upgrade.installed_version, upgrade.paket.entry.version);
+/// }
+/// ```
+pub fn plan_upgrade(
+    installer: &Installer,
+    repositories: &[RemoteRepository],
+    install_recommends: bool,
+) -> Result<UpgradePlan> {
+    let installed = installer.installed_versions()?;
+    let installed_pakets = installer.installed_pakets()?;
+
+    let mut plan = UpgradePlan::default();
+    let mut upgrades = find_upgrades(repositories, &installed);
+
+    let pakets = loop {
+        let chosen: Vec<RemotePaket> = upgrades.iter().map(|u| u.paket.clone()).collect();
+        if chosen.is_empty() {
+            return Ok(plan);
+        }
+
+        let pakets =
+            remote::resolve_dependencies(repositories, chosen, &installed, install_recommends)?;
+
+        match find_broken_dependency(&installed_pakets, &installed, &pakets)? {
+            None => break pakets,
+            Some(broken) => hold_back(
+                repositories,
+                &installed,
+                install_recommends,
+                &installed_pakets,
+                broken,
+                &mut upgrades,
+                &mut plan.held_back,
+            )?,
+        }
+    };
+
+    for paket in pakets {
+        match installed.get(&paket.entry.name) {
+            Some(installed_version) if !is_newer(&paket, installed_version) => {
+                return Err(PaketError::DependencyError(format!(
+                    "{} {} is installed, the upgrade requires {} {}",
+                    paket.entry.name, installed_version, paket.entry.name, paket.entry.version
+                )));
+            }
+            Some(installed_version) => plan.upgrades.push(PaketUpgrade {
+                installed_version: installed_version.clone(),
+                paket,
+            }),
+            None => plan.new_pakets.push(paket),
+        }
+    }
+    plan.upgrades
+        .sort_by(|a, b| a.paket.entry.name.cmp(&b.paket.entry.name));
+
+    Ok(plan)
+}
+
+/// A dependency of an installed paket which doesn't accept the version chosen for the upgrade.
+struct BrokenDependency {
+    name: String,
+    version: Version,
+    required_by: String,
+}
+
+/// Find an installed paket which is not upgraded and doesn't accept the version of its dependency after the upgrade.
+fn find_broken_dependency(
+    installed_pakets: &[Config],
+    installed: &HashMap<String, Version>,
+    pakets: &[RemotePaket],
+) -> Result<Option<BrokenDependency>> {
+    // Versions of the system after the upgrade
+    let mut versions = installed.clone();
+    for paket in pakets {
+        if let Ok(version) = Version::parse(&paket.entry.version) {
+            versions.insert(paket.entry.name.clone(), version);
+        }
+    }
+
+    for config in installed_pakets {
+        if pakets.iter().any(|p| p.entry.name == config.package.name) {
+            continue;
+        }
+
+        for (name, requirement) in dependency::required_dependencies(config)? {
+            if let Some(version) = versions.get(&name) {
+                if !requirement.matches(version) {
+                    return Ok(Some(BrokenDependency {
+                        version: version.clone(),
+                        required_by: format!("{} ({requirement})", config.package.name),
+                        name,
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Choose a lower version of the broken dependency which every installed paket accepts, or hold back its upgrade.
+///
+/// If the broken dependency isn't upgraded itself but required by the new version of an upgraded paket, that upgrade
+/// is held back.
+fn hold_back(
+    repositories: &[RemoteRepository],
+    installed: &HashMap<String, Version>,
+    install_recommends: bool,
+    installed_pakets: &[Config],
+    broken: BrokenDependency,
+    upgrades: &mut Vec<PaketUpgrade>,
+    held_back: &mut Vec<HeldBackPaket>,
+) -> Result<()> {
+    let mut held = |upgrade: PaketUpgrade| {
+        held_back.push(HeldBackPaket {
+            name: upgrade.paket.entry.name,
+            installed_version: upgrade.installed_version,
+            available_version: upgrade.paket.entry.version,
+            required_by: broken.required_by.clone(),
+        })
+    };
+
+    if let Some(i) = upgrades
+        .iter()
+        .position(|u| u.paket.entry.name == broken.name)
+    {
+        // Requirements of the installed pakets which are not upgraded, the new version must match all of them
+        let mut requirement = VersionReq::STAR;
+        for config in installed_pakets.iter().filter(|c| {
+            !upgrades
+                .iter()
+                .any(|u| u.paket.entry.name == c.package.name)
+        }) {
+            for (name, r) in dependency::required_dependencies(config)? {
+                if name == broken.name {
+                    requirement.comparators.extend(r.comparators);
+                }
+            }
+        }
+
+        let upgrade = &upgrades[i];
+        match remote::find_paket(repositories, &broken.name, &requirement)
+            .filter(|p| is_newer(p, &upgrade.installed_version))
+        {
+            Some(paket) => upgrades[i].paket = paket,
+            None => held(upgrades.remove(i)),
+        }
+
+        return Ok(());
+    }
+
+    // Upgrades which require the broken version
+    let upgrade_count = upgrades.len();
+    let mut i = 0;
+    while i < upgrades.len() {
+        let pakets = remote::resolve_dependencies(
+            repositories,
+            vec![upgrades[i].paket.clone()],
+            installed,
+            install_recommends,
+        )?;
+
+        match pakets.iter().any(|p| p.entry.name == broken.name) {
+            true => held(upgrades.remove(i)),
+            false => i += 1,
+        }
+    }
+
+    if upgrades.len() == upgrade_count {
+        return Err(PaketError::DependencyError(format!(
+            "Upgrading {} to {} breaks {}",
+            broken.name, broken.version, broken.required_by
+        )));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
+use crate::install::Installer;
+use crate::sha256;
+use crate::toml_structs::installed_toml::InstalledFile;
+use crate::{PaketError, Result};
+
+/// A difference between an installed file and its installed record.
+#[derive(Debug, PartialEq, Clone)]
+pub enum FileProblem {
+    /// File doesn't exist anymore.
+    Missing,
+
+    /// SHA256 of the file content is different.
+    ContentChanged,
+
+    /// Permission bits are different. (e.g. `0o755` -> `0o777`)
+    ModeChanged { expected: u32, actual: u32 },
+
+    /// Owner user or group is different as `(uid, gid)`.
+    OwnerChanged {
+        expected: (u32, u32),
+        actual: (u32, u32),
+    },
+}
+
+impl fmt::Display for FileProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileProblem::Missing => write!(f, "is missing"),
+            FileProblem::ContentChanged => write!(f, "content is changed"),
+            FileProblem::ModeChanged { expected, actual } => {
+                write!(f, "mode is changed: {expected:#o} -> {actual:#o}")
+            }
+            FileProblem::OwnerChanged { expected, actual } => write!(
+                f,
+                "owner is changed: {}:{} -> {}:{}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+        }
+    }
+}
+
+/// Problems of an installed file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FileReport {
+    /// Absolute path of the file in the installed system.
+    pub path: String,
+
+    /// The file is a configuration file which is expected to be modified.
+    pub config: bool,
+
+    pub problems: Vec<FileProblem>,
+}
+
+impl FileReport {
+    /// Content changes of the configuration files are not failures, they are modified by the users.
+    pub fn is_failure(&self) -> bool {
+        self.problems
+            .iter()
+            .any(|p| !(self.config && *p == FileProblem::ContentChanged))
+    }
+}
+
+/// Verification result of an installed paket.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PaketReport {
+    pub name: String,
+    pub version: String,
+
+    /// Count of the checked files.
+    pub checked_files: usize,
+
+    /// Only the files which have problems.
+    pub files: Vec<FileReport>,
+}
+
+impl PaketReport {
+    /// Check if all files of the paket are same as installed. See: `FileReport::is_failure`
+    pub fn is_ok(&self) -> bool {
+        !self.files.iter().any(|f| f.is_failure())
+    }
+}
+
+/// Compare an installed file with its record.
+fn verify_file(installer: &Installer, installed_file: &InstalledFile) -> Result<Vec<FileProblem>> {
+    let host_path = installer.host_path(&installed_file.path);
+
+    let metadata = match fs::symlink_metadata(&host_path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(vec![FileProblem::Missing])
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut problems = Vec::new();
+
+    if let Some(expected) = &installed_file.sha256 {
+        if !metadata.is_file() || sha256::calculate_sha256(&fs::read(&host_path)?) != *expected {
+            problems.push(FileProblem::ContentChanged);
+        }
+    }
+
+    if let Some(expected) = installed_file.mode {
+        let actual = metadata.mode() & 0o7777;
+        if actual != expected {
+            problems.push(FileProblem::ModeChanged { expected, actual });
+        }
+    }
+
+    if let (Some(uid), Some(gid)) = (installed_file.uid, installed_file.gid) {
+        let actual = (metadata.uid(), metadata.gid());
+        if actual != (uid, gid) {
+            problems.push(FileProblem::OwnerChanged {
+                expected: (uid, gid),
+                actual,
+            });
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Check the files of an installed paket are same as installed: existence, content, mode and owner.
+///
+/// Example:
+/// ```rust,no_run
+/// use libpaket::install::Installer;
+///
+/// let report = libpaket::verify::verify_paket(&Installer::default(), "my-helloworld-app").unwrap();
+/// for file in &report.files {
+///     println!("{}: {:?}", file.path, file.problems);
+/// }
+/// assert!(report.is_ok());
+/// ```
+pub fn verify_paket(installer: &Installer, paket_name: &str) -> Result<PaketReport> {
+    let config = installer
+        .installed_pakets()?
+        .into_iter()
+        .find(|c| c.package.name == paket_name)
+        .ok_or_else(|| PaketError::NotInstalled(paket_name.to_string()))?;
+
+    let installed_files = installer.installed_files(paket_name)?;
+
+    let mut files = Vec::new();
+    for installed_file in &installed_files {
+        let problems = verify_file(installer, installed_file)?;
+
+        if !problems.is_empty() {
+            files.push(FileReport {
+                path: installed_file.path.clone(),
+                config: installed_file.config,
+                problems,
+            });
+        }
+    }
+
+    Ok(PaketReport {
+        name: config.package.name,
+        version: config.package.version,
+        checked_files: installed_files.len(),
+        files,
+    })
+}
+
+/// Verify the installed pakets by their names. All installed pakets are verified if `paket_names` is empty.
+pub fn verify_pakets(installer: &Installer, paket_names: &[String]) -> Result<Vec<PaketReport>> {
+    let paket_names: Vec<String> = match paket_names.is_empty() {
+        true => {
+            let mut names: Vec<String> = installer
+                .installed_pakets()?
+                .into_iter()
+                .map(|c| c.package.name)
+                .collect();
+            names.sort();
+
+            names
+        }
+        false => paket_names.to_vec(),
+    };
+
+    paket_names
+        .iter()
+        .map(|name| verify_paket(installer, name))
+        .collect()
+}
@@ -0,0 +1,86 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::common::build_paket;
+    use libpaket::bootstrap::bootstrap;
+    use libpaket::PaketError;
+
+    /// Build a configuration paket which depends on `dependencies` and installs `/usr/share/<name>/<name>.txt`
+    fn build_paket_with_dependencies(folder: &Path, name: &str, dependencies: &str) -> PathBuf {
+        build_paket(
+            folder,
+            name,
+            "1.0.0",
+            &format!("\n[dependencies.library]\n{dependencies}"),
+            &[(&format!("usr/share/{name}/{name}.txt"), name)],
+        )
+    }
+
+    #[test]
+    fn bootstrap_root_filesystem() -> Result<(), Box<dyn std::error::Error>> {
+        let pakets = tempfile::tempdir()?;
+        let rootfs = tempfile::tempdir()?;
+        let root = rootfs.path().join("rootfs");
+
+        let base = build_paket_with_dependencies(pakets.path(), "bootstrap-base", "");
+        let shell = build_paket_with_dependencies(
+            pakets.path(),
+            "bootstrap-shell",
+            r#"bootstrap-base = "1""#,
+        );
+
+        // Missing dependency
+        assert!(matches!(
+            bootstrap(&root, std::slice::from_ref(&shell)),
+            Err(PaketError::DependencyError(_))
+        ));
+        assert!(!root.exists());
+
+        // A failing installation leaves the root folder as it was found
+        let fails = build_paket(
+            pakets.path(),
+            "bootstrap-fails",
+            "1.0.0",
+            "\n[scripts]\npre_install = \"fail.sh\"",
+            &[("fail.sh", "#!/bin/sh\nexit 1\n")],
+        );
+        fs::create_dir_all(&root)?;
+        assert!(matches!(
+            bootstrap(&root, &[base.clone(), fails]),
+            Err(PaketError::ScriptError(_))
+        ));
+        assert_eq!(0, fs::read_dir(&root)?.count());
+
+        let installer = bootstrap(&root, &[shell, base])?;
+
+        assert!(root.join("etc/skel").is_dir());
+        assert!(root.join("tmp").is_dir());
+        assert!(root
+            .join("usr/share/bootstrap-base/bootstrap-base.txt")
+            .is_file());
+        assert!(root
+            .join("usr/share/bootstrap-shell/bootstrap-shell.txt")
+            .is_file());
+
+        let mut installed: Vec<String> = installer
+            .installed_pakets()?
+            .into_iter()
+            .map(|c| c.package.name)
+            .collect();
+        installed.sort();
+        assert_eq!(vec!["bootstrap-base", "bootstrap-shell"], installed);
+
+        // Root folder must be empty
+        assert!(matches!(
+            bootstrap(&root, &[]),
+            Err(PaketError::FolderNotEmpty(_))
+        ));
+
+        Ok(())
+    }
+}
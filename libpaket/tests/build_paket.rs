@@ -1,19 +1,26 @@
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::Read;
     use std::path::Path;
 
     use libpaket::build::*;
+    use libpaket::sha256::calculate_sha256;
 
     macro_rules! folder_test {
         ($name:tt) => {
             #[test]
             fn $name() {
-                let _ = create_paket_from_toml(Path::new(concat!(
-                    "./example_pakets/",
-                    stringify!($name)
-                )))
+                let folder = tempfile::tempdir().unwrap();
+                let (paket_path, _file) = create_paket_from_toml(
+                    Path::new(concat!("./example_pakets/", stringify!($name))),
+                    folder.path(),
+                )
                 .unwrap();
+
+                assert_eq!(Some(folder.path()), paket_path.parent());
             }
         };
     }
@@ -24,4 +31,30 @@ mod tests {
     folder_test!(application_paket_with_assets);
     folder_test!(script_paket);
     folder_test!(script_paket_with_assets);
+
+    #[test]
+    fn sha256sum_is_the_hash_of_data() -> Result<(), Box<dyn std::error::Error>> {
+        let folder = tempfile::tempdir()?;
+        let (paket_path, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            folder.path(),
+        )?;
+
+        let mut entries = HashMap::new();
+        let mut archive = tar::Archive::new(File::open(&paket_path)?);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().to_string();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            entries.insert(path, content);
+        }
+
+        assert_eq!(
+            format!("{}\n", calculate_sha256(&entries["data.tar.gz"])),
+            String::from_utf8(entries["SHA256SUM"].clone())?
+        );
+
+        Ok(())
+    }
 }
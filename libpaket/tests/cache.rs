@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use libpaket::cache::*;
+
+    /// Write a fake .paket file into the cache, downloaded at `download_time` seconds.
+    fn add_cached(cache_dir: &Path, name: &str, version: &str, sha256: &str, download_time: u64) {
+        let folder = cache_dir.join(name).join(version).join(sha256);
+        fs::create_dir_all(&folder).unwrap();
+
+        let path = folder.join(format!("{name}_{version}.paket"));
+        fs::write(&path, vec![0; 100]).unwrap();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(download_time))
+            .unwrap();
+    }
+
+    fn versions(pakets: &[CachedPaket]) -> Vec<String> {
+        pakets
+            .iter()
+            .map(|p| format!("{} {} {}", p.name, p.version, p.sha256))
+            .collect()
+    }
+
+    fn fill_cache(cache_dir: &Path) {
+        add_cached(cache_dir, "vlc", "3.0.20", "aaaa", 1000);
+        add_cached(cache_dir, "vlc", "3.0.9", "bbbb", 3000);
+        add_cached(cache_dir, "vlc", "3.0.21", "cccc", 2000);
+        add_cached(cache_dir, "vlc", "3.0.21", "dddd", 4000);
+        add_cached(cache_dir, "hello", "0.1.0", "eeee", 5000);
+    }
+
+    #[test]
+    fn list_and_clean_cache() -> Result<(), Box<dyn std::error::Error>> {
+        let cache = tempfile::tempdir()?;
+        let cache_dir = cache.path().join("cache");
+        assert_eq!(Vec::<CachedPaket>::new(), list_cache(&cache_dir)?);
+
+        fill_cache(&cache_dir);
+        fs::write(cache_dir.join("interrupted.paket.part"), "")?;
+
+        let cached = list_cache(&cache_dir)?;
+        assert_eq!(
+            vec![
+                "hello 0.1.0 eeee",
+                "vlc 3.0.9 bbbb",
+                "vlc 3.0.20 aaaa",
+                "vlc 3.0.21 cccc",
+                "vlc 3.0.21 dddd",
+            ],
+            versions(&cached)
+        );
+        assert_eq!(100, cached[0].size);
+
+        // Newest 2 versions of every paket
+        let removed = clean_cache(
+            &cache_dir,
+            &CleanOptions {
+                keep_versions: Some(2),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(vec!["vlc 3.0.9 bbbb"], versions(&removed));
+        assert!(!cache_dir.join("vlc/3.0.9").exists());
+        assert!(cache_dir.join("vlc").exists());
+
+        // Oldest downloads until the cache fits
+        let removed = clean_cache(
+            &cache_dir,
+            &CleanOptions {
+                max_size: Some(250),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            vec!["vlc 3.0.20 aaaa", "vlc 3.0.21 cccc"],
+            versions(&removed)
+        );
+        assert_eq!(
+            vec!["hello 0.1.0 eeee", "vlc 3.0.21 dddd"],
+            versions(&list_cache(&cache_dir)?)
+        );
+
+        // Everything
+        let removed = clean_cache(&cache_dir, &CleanOptions::default())?;
+        assert_eq!(2, removed.len());
+        assert_eq!(0, fs::read_dir(&cache_dir)?.count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cache_sizes() {
+        assert_eq!(Some(1024), parse_size("1024"));
+        assert_eq!(Some(2 * 1024 * 1024 * 1024), parse_size("2G"));
+        assert_eq!(Some(1536), parse_size("1.5 KiB"));
+        assert_eq!(Some(500 * 1024 * 1024), parse_size("500mb"));
+        assert_eq!(None, parse_size(""));
+        assert_eq!(None, parse_size("10X"));
+    }
+}
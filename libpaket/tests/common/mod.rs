@@ -0,0 +1,47 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use libpaket::build::create_paket_from_toml;
+
+/// Build a configuration paket into `folder` and return the path of the .paket file.
+///
+/// `extra_toml` is written right after the `[package]` keys, so it can add more package keys or new tables.
+/// `files` are `(path in the paket folder, content)` pairs.
+pub fn build_paket(
+    folder: &Path,
+    name: &str,
+    version: &str,
+    extra_toml: &str,
+    files: &[(&str, &str)],
+) -> PathBuf {
+    let paket_folder = folder.join(format!("{name}_{version}"));
+    fs::create_dir_all(&paket_folder).unwrap();
+
+    fs::write(
+        paket_folder.join("Paket.toml"),
+        format!(
+            r#"
+[package]
+name = "{name}"
+type = "configuration"
+version = "{version}"
+maintainers = ["Emin Fedar <eminfedar@gmail.com>"]
+description = "Test paket"
+license = "MIT"
+architectures = ["any"]
+{extra_toml}
+"#
+        ),
+    )
+    .unwrap();
+
+    for (path, content) in files {
+        let path = paket_folder.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    let (paket_path, _file) = create_paket_from_toml(&paket_folder, folder).unwrap();
+
+    paket_path
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+
+    use libpaket::config::*;
+    use libpaket::install::Installer;
+    use libpaket::toml_structs::global_toml::{ColorMode, GlobalConfig};
+    use libpaket::PaketError;
+
+    #[test]
+    fn read_global_config() -> Result<(), Box<dyn std::error::Error>> {
+        let folder = tempfile::tempdir()?;
+        let config_path = folder.path().join("paket.toml");
+
+        // Missing file is the default configuration
+        assert_eq!(GlobalConfig::default(), read_config(&config_path)?);
+
+        fs::write(
+            &config_path,
+            "cache_dir = \"/var/cache/paket\"\ninstall_recommends = false\ncolor = \"never\"\n",
+        )?;
+        let config = read_config(&config_path)?;
+        assert_eq!(
+            GlobalConfig {
+                cache_dir: Some(PathBuf::from("/var/cache/paket")),
+                install_recommends: false,
+                color: ColorMode::Never,
+                ..Default::default()
+            },
+            config
+        );
+        assert!(!use_color(&config));
+        assert_eq!(None, max_cache_size(&config)?);
+
+        let installer = Installer::new("/mnt");
+        assert_eq!(
+            PathBuf::from("/var/cache/paket"),
+            cache_dir(&config, &installer)
+        );
+        assert_eq!(
+            PathBuf::from("/mnt/var/lib/paket/cache"),
+            cache_dir(&GlobalConfig::default(), &installer)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn override_config_with_env() -> Result<(), Box<dyn std::error::Error>> {
+        let env = HashMap::from([
+            ("PAKET_ROOT", "/mnt"),
+            ("PAKET_PARALLEL_DOWNLOADS", "8"),
+            ("PAKET_INSTALL_RECOMMENDS", "0"),
+            ("PAKET_CACHE_DIR", ""),
+            ("NO_COLOR", "1"),
+            ("PAKET_LANGUAGE", "tr_TR.UTF-8"),
+        ]);
+        let config = with_env_overrides(GlobalConfig::default(), |name| {
+            env.get(name).map(|v| v.to_string())
+        })?;
+
+        assert_eq!(
+            GlobalConfig {
+                root: PathBuf::from("/mnt"),
+                cache_dir: None,
+                max_cache_size: None,
+                parallel_downloads: 8,
+                install_recommends: false,
+                color: ColorMode::Never,
+                language: Some(String::from("tr_TR.UTF-8")),
+            },
+            config
+        );
+
+        // PAKET_COLOR is used instead of NO_COLOR
+        let config = with_env_overrides(GlobalConfig::default(), |name| match name {
+            "NO_COLOR" => Some(String::from("1")),
+            "PAKET_COLOR" => Some(String::from("always")),
+            _ => None,
+        })?;
+        assert_eq!(ColorMode::Always, config.color);
+
+        let config = with_env_overrides(GlobalConfig::default(), |name| {
+            (name == "PAKET_MAX_CACHE_SIZE").then(|| String::from("500M"))
+        })?;
+        assert_eq!(Some(500 * 1024 * 1024), max_cache_size(&config)?);
+
+        assert!(matches!(
+            with_env_overrides(GlobalConfig::default(), |name| {
+                (name == "PAKET_PARALLEL_DOWNLOADS").then(|| String::from("many"))
+            }),
+            Err(PaketError::InvalidConfig(_))
+        ));
+
+        Ok(())
+    }
+}
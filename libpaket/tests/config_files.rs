@@ -0,0 +1,103 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::common::build_paket;
+    use libpaket::install::*;
+    use libpaket::sha256::calculate_sha256;
+    use libpaket::toml_structs::installed_toml::{InstalledFile, InstalledInfo};
+    use libpaket::toml_structs::paket_toml::read_toml_file;
+
+    /// Build a configuration paket with `/etc/config-test/app.conf` and `/usr/share/config-test/data.txt`
+    fn build_paket_with_config(folder: &Path, version: &str) -> PathBuf {
+        build_paket(
+            folder,
+            "config-test",
+            version,
+            "",
+            &[
+                ("etc/config-test/app.conf", version),
+                ("usr/share/config-test/data.txt", version),
+            ],
+        )
+    }
+
+    #[test]
+    fn modified_config_files_are_kept() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let v1 = build_paket_with_config(pakets.path(), "1.0.0");
+        let v2 = build_paket_with_config(pakets.path(), "2.0.0");
+        let v3 = build_paket_with_config(pakets.path(), "3.0.0");
+
+        let conf = root.path().join("etc/config-test/app.conf");
+        let paketnew = root.path().join("etc/config-test/app.conf.paketnew");
+        let data = root.path().join("usr/share/config-test/data.txt");
+
+        let v4 = build_paket_with_config(pakets.path(), "4.0.0");
+
+        let conf_record = |version: &str| -> InstalledFile {
+            let installed_info: InstalledInfo = read_toml_file(&root.path().join(format!(
+                "var/lib/paket/installed/configuration/config-test_{version}/Installed.toml"
+            )))
+            .unwrap();
+            installed_info
+                .files
+                .into_iter()
+                .find(|f| f.path == "/etc/config-test/app.conf")
+                .unwrap()
+        };
+
+        installer.install_paket(&v1)?;
+        let record = conf_record("1.0.0");
+        assert!(record.config);
+        assert!(!record.modified);
+        assert_eq!(Some(calculate_sha256(b"1.0.0")), record.sha256);
+
+        // Unmodified configuration file is replaced
+        installer.install_paket(&v2)?;
+        assert_eq!("2.0.0", fs::read_to_string(&conf)?);
+        assert!(!paketnew.exists());
+
+        // Modified configuration file is kept
+        fs::write(&conf, "my settings")?;
+        installer.install_paket(&v3)?;
+        assert_eq!("my settings", fs::read_to_string(&conf)?);
+        assert_eq!("3.0.0", fs::read_to_string(&paketnew)?);
+        assert_eq!("3.0.0", fs::read_to_string(&data)?);
+
+        // The file which is kept is recorded, not the new version
+        let record = conf_record("3.0.0");
+        assert_eq!(Some(calculate_sha256(b"my settings")), record.sha256);
+        assert!(record.modified);
+
+        // and it's still kept on the next upgrade
+        installer.install_paket(&v4)?;
+        assert_eq!("my settings", fs::read_to_string(&conf)?);
+        assert_eq!("4.0.0", fs::read_to_string(&paketnew)?);
+        assert!(conf_record("4.0.0").modified);
+
+        // Configuration files are kept on remove
+        installer.remove_paket("config-test")?;
+        assert!(!data.exists());
+        assert_eq!("my settings", fs::read_to_string(&conf)?);
+
+        // and removed on purge
+        installer.install_paket(&v4)?;
+        let installer = installer.with_options(InstallOptions {
+            purge_config_files: true,
+            ..Default::default()
+        });
+        installer.remove_paket("config-test")?;
+        assert!(!conf.exists());
+        assert!(!paketnew.exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::io::Read;
+    use std::path::{Path, PathBuf};
+
+    use libpaket::archive::EntryKind;
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::contents::*;
+    use libpaket::install::*;
+    use libpaket::PaketError;
+
+    #[test]
+    fn list_and_extract_paket_contents() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let output = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            pakets.path(),
+        )?;
+
+        let entries = paket_contents(&installer, Path::new(&paket))?;
+        let executable = entries
+            .iter()
+            .find(|e| e.path == Path::new("usr/bin/helloworld"))
+            .unwrap();
+        assert_eq!(EntryKind::File, executable.kind);
+        assert_ne!(0, executable.mode & 0o111);
+        assert!(executable.size > 0);
+        assert_eq!(
+            vec![PathBuf::from("/usr/bin/helloworld")],
+            executable.targets
+        );
+
+        extract_paket(Path::new(&paket), output.path())?;
+        assert_eq!(
+            executable.size,
+            fs::metadata(output.path().join("usr/bin/helloworld"))?.len()
+        );
+
+        // Nothing is installed
+        assert!(!root.path().join("usr").exists());
+        assert!(installer.installed_pakets()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn changed_pakets_are_not_listed_or_extracted() -> Result<(), Box<dyn std::error::Error>> {
+        let pakets = tempfile::tempdir()?;
+        let output = tempfile::tempdir()?;
+        let installer = Installer::new(pakets.path());
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            pakets.path(),
+        )?;
+
+        // Same paket with a different SHA256SUM
+        let mut archive = tar::Archive::new(fs::File::open(&paket)?);
+        let mut changed = tar::Builder::new(Vec::new());
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            if entry.path()? == Path::new("SHA256SUM") {
+                content = format!("{}\n", "0".repeat(64)).into_bytes();
+            }
+
+            let mut header = entry.header().clone();
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            changed.append(&header, content.as_slice())?;
+        }
+        let changed_paket = pakets.path().join("changed.paket");
+        fs::write(&changed_paket, changed.into_inner()?)?;
+
+        assert!(matches!(
+            paket_contents(&installer, &changed_paket),
+            Err(PaketError::ChecksumMismatch(_))
+        ));
+        assert!(matches!(
+            extract_paket(&changed_paket, &output.path().join("out")),
+            Err(PaketError::ChecksumMismatch(_))
+        ));
+        assert!(!output.path().join("out").exists());
+
+        Ok(())
+    }
+}
@@ -1,12 +1,35 @@
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    #[allow(unused_imports)]
     use std::{collections::HashSet, path::Path};
 
     use libpaket::dependency::*;
+    use libpaket::toml_structs::paket_toml::{read_config_from_str, Config};
+
+    fn library_config(name: &str, version: &str, dependencies: &str) -> Config {
+        read_config_from_str(&format!(
+            r#"
+[package]
+name = "{name}"
+type = "library"
+version = "{version}"
+maintainers = ["Emin Fedar <eminfedar@gmail.com>"]
+description = "Test paket"
+license = "MIT"
+architectures = ["any"]
+
+[dependencies.library]
+{dependencies}
+"#
+        ))
+        .unwrap()
+    }
 
     #[test]
     fn empty_dependency() -> Result<(), Box<dyn std::error::Error>> {
+        #[allow(unused_mut)]
         let mut dep_root = Dependency::new("test", "1.0.0".parse()?);
 
         let dependency_list = dep_root.list_all_dependencies();
@@ -85,4 +108,40 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn install_order_after_dependencies() -> Result<(), Box<dyn std::error::Error>> {
+        let app = library_config("app", "1.0.0", "libb = \"1\"\nliba = \"2\"");
+        let liba = library_config("liba", "2.1.0", "libc = \"1.2\"");
+        let libb = library_config("libb", "1.0.0", "liba = \"2\"");
+        let libc = library_config("libc", "1.2.3", "");
+
+        let order = resolve_install_order(&[&app, &liba, &libb, &libc], &HashMap::new())?;
+
+        assert_eq!(vec![3, 1, 2, 0], order);
+
+        Ok(())
+    }
+
+    #[test]
+    fn installed_dependency_is_satisfied() -> Result<(), Box<dyn std::error::Error>> {
+        let app = library_config("app", "1.0.0", "liba = \"2\"");
+        let installed = HashMap::from([("liba".to_string(), "2.4.0".parse()?)]);
+
+        assert_eq!(vec![0], resolve_install_order(&[&app], &installed)?);
+
+        // Installed version doesn't match the requirement
+        let installed = HashMap::from([("liba".to_string(), "1.0.0".parse()?)]);
+        assert!(resolve_install_order(&[&app], &installed).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn circular_dependency() {
+        let liba = library_config("liba", "1.0.0", "libb = \"1\"");
+        let libb = library_config("libb", "1.0.0", "liba = \"1\"");
+
+        assert!(resolve_install_order(&[&liba, &libb], &HashMap::new()).is_err());
+    }
 }
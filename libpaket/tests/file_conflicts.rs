@@ -0,0 +1,94 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::common::build_paket;
+    use libpaket::install::*;
+    use libpaket::owners::find_owners;
+    use libpaket::PaketError;
+
+    /// Build a paket with `/usr/share/conflict-test/shared.txt` and `/usr/share/conflict-test/<name>.txt`
+    fn build_paket_with_shared_file(folder: &Path, name: &str, replaces: &[&str]) -> PathBuf {
+        build_paket(
+            folder,
+            name,
+            "1.0.0",
+            &format!("replaces = {replaces:?}"),
+            &[
+                ("usr/share/conflict-test/shared.txt", name),
+                (&format!("usr/share/conflict-test/{name}.txt"), name),
+            ],
+        )
+    }
+
+    #[test]
+    fn conflicting_files_are_not_overwritten() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let first = build_paket_with_shared_file(pakets.path(), "conflict-first", &[]);
+        let second = build_paket_with_shared_file(pakets.path(), "conflict-second", &[]);
+        let shared = root.path().join("usr/share/conflict-test/shared.txt");
+
+        installer.install_paket(&first)?;
+
+        match installer.install_paket(&second) {
+            Err(PaketError::FileConflict(report)) => assert_eq!(
+                "/usr/share/conflict-test/shared.txt (conflict-first)",
+                report
+            ),
+            r => panic!("Expected a file conflict: {r:?}"),
+        }
+        assert_eq!("conflict-first", fs::read_to_string(&shared)?);
+        assert!(!root
+            .path()
+            .join("usr/share/conflict-test/conflict-second.txt")
+            .exists());
+
+        // Overwritten files are owned by the new paket
+        let installer = installer.with_options(InstallOptions {
+            force_overwrite: true,
+            ..Default::default()
+        });
+        installer.install_paket(&second)?;
+        assert_eq!("conflict-second", fs::read_to_string(&shared)?);
+        assert_eq!(
+            vec!["conflict-second".to_string()],
+            find_owners(&installer, "/usr/share/conflict-test/shared.txt")?[0].pakets
+        );
+
+        installer.remove_paket("conflict-first")?;
+        assert_eq!("conflict-second", fs::read_to_string(&shared)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn replaced_paket_files_are_overwritten() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let old = build_paket_with_shared_file(pakets.path(), "replace-old", &[]);
+        let new = build_paket_with_shared_file(pakets.path(), "replace-new", &["replace-old"]);
+
+        installer.install_paket(&old)?;
+        installer.install_paket(&new)?;
+
+        assert_eq!(
+            "replace-new",
+            fs::read_to_string(root.path().join("usr/share/conflict-test/shared.txt"))?
+        );
+        assert_eq!(
+            vec!["replace-new".to_string()],
+            find_owners(&installer, "/usr/share/conflict-test/shared.txt")?[0].pakets
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::os::unix::fs::MetadataExt;
+    use std::path::{Path, PathBuf};
+
+    use crate::common::build_paket;
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::*;
+    use libpaket::placeholder::UserHomePolicy;
+    use libpaket::sha256::calculate_sha256;
+    use libpaket::toml_structs::installed_toml::InstalledInfo;
+    use libpaket::toml_structs::paket_toml::read_toml_file;
+    use libpaket::PaketError;
+
+    fn build_example(folder: &Path, name: &str) -> PathBuf {
+        let (paket_path, _file) =
+            create_paket_from_toml(&Path::new("./example_pakets").join(name), folder).unwrap();
+
+        paket_path
+    }
+
+    /// Build a configuration paket which installs the `files` under `/usr/share/<name>/`
+    fn build_configuration_paket(
+        folder: &Path,
+        name: &str,
+        version: &str,
+        files: &[&str],
+    ) -> PathBuf {
+        let files: Vec<(String, &str)> = files
+            .iter()
+            .map(|f| (format!("usr/share/{name}/{f}"), version))
+            .collect();
+        let files: Vec<(&str, &str)> = files.iter().map(|(p, c)| (p.as_str(), *c)).collect();
+
+        build_paket(folder, name, version, "", &files)
+    }
+
+    #[test]
+    fn install_and_remove_application() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let paket = build_example(pakets.path(), "application_paket_with_assets");
+
+        installer.install_paket_files(std::slice::from_ref(&paket))?;
+
+        let installed_files = [
+            "usr/bin/helloworld",
+            "usr/share/icons/hicolor/scalable/apps/helloworld.svg",
+            "usr/share/applications/my-helloworld-app.desktop",
+            "usr/share/my-helloworld-app/assets/pardus-logo.svg",
+        ];
+        for f in installed_files {
+            assert!(root.path().join(f).is_file(), "{f} is not installed");
+        }
+
+        let record = root
+            .path()
+            .join("var/lib/paket/installed/application/my-helloworld-app_0.1.0");
+        assert!(record.join("Paket.toml").is_file());
+
+        let installed_info: InstalledInfo = read_toml_file(&record.join("Installed.toml"))?;
+        assert!(installed_info
+            .files
+            .iter()
+            .any(|f| f.path == "/usr/bin/helloworld"));
+
+        // Installing the same version again does nothing
+        assert!(matches!(
+            installer.install_paket(&paket)?,
+            PaketExistance::SameVersionInstalled
+        ));
+
+        installer.remove_paket("my-helloworld-app")?;
+        for f in installed_files {
+            assert!(!root.path().join(f).exists(), "{f} is not removed");
+        }
+        assert!(!record.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn install_user_home_files_to_all_users() -> Result<(), Box<dyn std::error::Error>> {
+        let uid = fs::metadata("/proc/self")?.uid();
+        // Only root can give the files to another user
+        if uid != 0 && uid < 1000 {
+            return Ok(());
+        }
+
+        let root = tempfile::tempdir()?;
+        let gid = fs::metadata("/proc/self")?.gid();
+        fs::create_dir_all(root.path().join("etc"))?;
+        fs::create_dir_all(root.path().join("home/tester"))?;
+        fs::write(
+            root.path().join("etc/passwd"),
+            format!(
+                "tester:x:{}:{gid}:Tester:/home/tester:/bin/bash\n",
+                uid.max(1000)
+            ),
+        )?;
+
+        let installer = Installer::new(root.path()).with_options(InstallOptions {
+            home_policy: UserHomePolicy::AllUsers,
+            ..Default::default()
+        });
+        let pakets = tempfile::tempdir()?;
+        let paket = build_example(pakets.path(), "configuration_paket_with_user_home");
+
+        installer.install_paket(&paket)?;
+
+        let user_font = root.path().join("home/tester/.fonts/hack/Hack-Bold.ttf");
+        let skel_font = root.path().join("etc/skel/.fonts/hack/Hack-Bold.ttf");
+        assert!(user_font.is_file());
+        assert!(skel_font.is_file());
+        assert_eq!(fs::metadata(&user_font)?.uid(), uid.max(1000));
+
+        let installed_info: InstalledInfo = read_toml_file(&root.path().join(
+            "var/lib/paket/installed/configuration/font-hack-user-only_3.3.0/Installed.toml",
+        ))?;
+        let user_file = installed_info
+            .files
+            .iter()
+            .find(|f| f.path == "/home/tester/.fonts/hack/Hack-Bold.ttf")
+            .unwrap();
+        assert_eq!(user_file.user.as_deref(), Some("tester"));
+
+        installer.remove_paket("font-hack-user-only")?;
+        assert!(!user_font.exists());
+        assert!(!skel_font.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn upgrade_removes_old_files() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let v1 = build_configuration_paket(
+            pakets.path(),
+            "upgrade-test",
+            "1.0.0",
+            &["common.txt", "old.txt"],
+        );
+        let v2 = build_configuration_paket(
+            pakets.path(),
+            "upgrade-test",
+            "2.0.0",
+            &["common.txt", "new.txt"],
+        );
+
+        installer.install_paket(&v1)?;
+        assert!(matches!(
+            installer.install_paket(&v2)?,
+            PaketExistance::LowerVersionInstalled
+        ));
+
+        let share = root.path().join("usr/share/upgrade-test");
+        assert_eq!(fs::read_to_string(share.join("common.txt"))?, "2.0.0");
+        assert!(share.join("new.txt").is_file());
+        assert!(!share.join("old.txt").exists());
+
+        let installed = root.path().join("var/lib/paket/installed/configuration");
+        assert!(!installed.join("upgrade-test_1.0.0").exists());
+        assert!(installed.join("upgrade-test_2.0.0").exists());
+
+        // Downgrade is not done
+        assert!(matches!(
+            installer.install_paket(&v1)?,
+            PaketExistance::HigherVersionInstalled
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pakets_are_found_by_their_exact_names() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let foo_bar = build_configuration_paket(pakets.path(), "foo_bar", "1.0.0", &["a.txt"]);
+        let foo = build_configuration_paket(pakets.path(), "foo", "2.0.0", &["a.txt"]);
+
+        // `foo` is not installed, `foo_bar` must not be taken for it
+        installer.install_paket(&foo_bar)?;
+        assert!(matches!(
+            installer.remove_paket("foo"),
+            Err(PaketError::NotInstalled(_))
+        ));
+        assert!(matches!(
+            installer.install_paket(&foo)?,
+            PaketExistance::NotExists
+        ));
+
+        assert_eq!(
+            "1.0.0",
+            installer.installed_versions()?["foo_bar"].to_string()
+        );
+        assert_eq!("2.0.0", installer.installed_versions()?["foo"].to_string());
+
+        installer.remove_paket("foo")?;
+        assert!(!root.path().join("usr/share/foo/a.txt").exists());
+        assert!(root.path().join("usr/share/foo_bar/a.txt").exists());
+        assert!(installer.installed_info("foo_bar").is_ok());
+
+        Ok(())
+    }
+
+    /// Replace the data.tar.gz of a paket with a symlink to `link_target` and a file written through the symlink.
+    fn build_hostile_paket(folder: &Path, link_target: &Path) -> PathBuf {
+        let paket_path = build_configuration_paket(folder, "hostile", "1.0.0", &["a.txt"]);
+
+        let mut data = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_mode(0o777);
+        data.append_link(&mut header, "usr/lib/x", link_target)
+            .unwrap();
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(6);
+        header.set_mode(0o644);
+        header.set_cksum();
+        data.append_data(&mut header, "usr/lib/x/passwd", &b"hacked"[..])
+            .unwrap();
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&data.into_inner().unwrap()).unwrap();
+        let compressed_data = encoder.finish().unwrap();
+
+        // Keep Paket.toml, replace SHA256SUM and data.tar.gz
+        let mut paket_toml = Vec::new();
+        let mut archive = tar::Archive::new(fs::File::open(&paket_path).unwrap());
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            if entry.path().unwrap() == Path::new("Paket.toml") {
+                entry.read_to_end(&mut paket_toml).unwrap();
+            }
+        }
+
+        let mut paket = tar::Builder::new(Vec::new());
+        for (name, content) in [
+            ("Paket.toml", paket_toml),
+            (
+                "SHA256SUM",
+                format!("{}\n", calculate_sha256(&compressed_data)).into_bytes(),
+            ),
+            ("data.tar.gz", compressed_data),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            paket
+                .append_data(&mut header, name, content.as_slice())
+                .unwrap();
+        }
+        fs::write(&paket_path, paket.into_inner().unwrap()).unwrap();
+
+        paket_path
+    }
+
+    #[test]
+    fn files_are_not_written_through_symlinks() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let outside = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        for link_target in [outside.path(), Path::new("../../etc")] {
+            let paket = build_hostile_paket(pakets.path(), link_target);
+
+            assert!(matches!(
+                installer.install_paket(&paket),
+                Err(PaketError::InvalidPaketPath(_))
+            ));
+            assert!(!outside.path().join("passwd").exists());
+            assert!(!root.path().join("etc/passwd").exists());
+
+            // The symlink is rolled back too
+            assert!(fs::symlink_metadata(root.path().join("usr/lib/x")).is_err());
+        }
+
+        Ok(())
+    }
+}
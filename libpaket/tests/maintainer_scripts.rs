@@ -0,0 +1,159 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::common::build_paket;
+    use libpaket::install::*;
+    use libpaket::PaketError;
+
+    const LOG_SCRIPT: &str = "#!/bin/sh\necho \"$PAKET_PHASE $PAKET_OLD_VERSION $PAKET_NEW_VERSION\" >> \"$PAKET_ROOT/scripts.log\"\n";
+    const FAILING_SCRIPT: &str = "#!/bin/sh\nexit 1\n";
+
+    /// Build a configuration paket with the maintainer `scripts` as `(phase, content)`
+    fn build_paket_with_scripts(
+        folder: &Path,
+        name: &str,
+        version: &str,
+        scripts: &[(&str, &str)],
+    ) -> PathBuf {
+        let mut files = vec![(format!("usr/share/{name}/data.txt"), version)];
+        let mut scripts_table = String::from("\n[scripts]\n");
+        for (phase, content) in scripts {
+            files.push((format!("{phase}.sh"), content));
+            scripts_table.push_str(&format!("{phase} = \"{phase}.sh\"\n"));
+        }
+
+        let files: Vec<(&str, &str)> = files.iter().map(|(p, c)| (p.as_str(), *c)).collect();
+        build_paket(folder, name, version, &scripts_table, &files)
+    }
+
+    #[test]
+    fn scripts_run_with_versions() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let all_phases = [
+            ("pre_install", LOG_SCRIPT),
+            ("post_install", LOG_SCRIPT),
+            ("pre_remove", LOG_SCRIPT),
+            ("post_remove", LOG_SCRIPT),
+            ("post_upgrade", LOG_SCRIPT),
+        ];
+        let v1 = build_paket_with_scripts(pakets.path(), "scripts-test", "1.0.0", &all_phases);
+        let v2 = build_paket_with_scripts(pakets.path(), "scripts-test", "2.0.0", &all_phases);
+
+        installer.install_paket(&v1)?;
+        installer.install_paket(&v2)?;
+        installer.remove_paket("scripts-test")?;
+
+        let log = fs::read_to_string(root.path().join("scripts.log"))?;
+        assert_eq!(
+            "pre_install  1.0.0\n\
+             post_install  1.0.0\n\
+             pre_install 1.0.0 2.0.0\n\
+             post_upgrade 1.0.0 2.0.0\n\
+             pre_remove 2.0.0 \n\
+             post_remove 2.0.0 \n",
+            log
+        );
+
+        // Scripts are not installed as files
+        assert!(!root.path().join("pre_install.sh").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn failing_script_aborts_installation() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let pre_install_fails = build_paket_with_scripts(
+            pakets.path(),
+            "pre-install-fails",
+            "1.0.0",
+            &[("pre_install", FAILING_SCRIPT)],
+        );
+        let post_install_fails = build_paket_with_scripts(
+            pakets.path(),
+            "post-install-fails",
+            "1.0.0",
+            &[("post_install", FAILING_SCRIPT)],
+        );
+
+        assert!(matches!(
+            installer.install_paket(&pre_install_fails),
+            Err(PaketError::ScriptError(_))
+        ));
+        assert!(!root.path().join("usr/share/pre-install-fails").exists());
+
+        // New installation is rolled back
+        assert!(matches!(
+            installer.install_paket(&post_install_fails),
+            Err(PaketError::ScriptError(_))
+        ));
+        assert!(!root
+            .path()
+            .join("usr/share/post-install-fails/data.txt")
+            .exists());
+        assert!(installer.installed_pakets()?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn failing_script_rolls_back_all_pakets() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let v1 = build_paket_with_scripts(pakets.path(), "batch-upgrade", "1.0.0", &[]);
+        installer.install_paket(&v1)?;
+
+        let v2 = build_paket_with_scripts(pakets.path(), "batch-upgrade", "2.0.0", &[]);
+        let new = build_paket_with_scripts(pakets.path(), "batch-new", "1.0.0", &[]);
+        let post_install_fails = build_paket_with_scripts(
+            pakets.path(),
+            "batch-fails",
+            "1.0.0",
+            &[("post_install", FAILING_SCRIPT)],
+        );
+
+        assert!(matches!(
+            installer.install_paket_files(&[v2, new, post_install_fails]),
+            Err(PaketError::ScriptError(_))
+        ));
+
+        // Pakets installed before the failing one are rolled back too
+        assert_eq!(
+            vec![String::from("batch-upgrade")],
+            installer
+                .installed_pakets()?
+                .into_iter()
+                .map(|c| c.package.name)
+                .collect::<Vec<String>>()
+        );
+        assert_eq!(
+            "1.0.0",
+            installer.installed_versions()?["batch-upgrade"].to_string()
+        );
+        assert_eq!(
+            "1.0.0",
+            fs::read_to_string(root.path().join("usr/share/batch-upgrade/data.txt"))?
+        );
+        assert!(!root.path().join("usr/share/batch-new/data.txt").exists());
+        assert!(!root.path().join("usr/share/batch-fails/data.txt").exists());
+        assert!(
+            libpaket::owners::find_owners(&installer, "/usr/share/batch-new/data.txt")?.is_empty()
+        );
+        assert!(!installer.get_state_dir().join("transaction").exists());
+
+        Ok(())
+    }
+}
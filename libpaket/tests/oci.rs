@@ -0,0 +1,115 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::io::Read;
+    use std::path::Path;
+
+    use flate2::read::GzDecoder;
+    use libpaket::bootstrap::bootstrap;
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::oci::*;
+    use libpaket::sha256::calculate_sha256;
+    use serde_json::Value;
+
+    /// Read all files of the image tarball
+    fn read_image(image_path: &Path) -> HashMap<String, Vec<u8>> {
+        let mut archive = tar::Archive::new(File::open(image_path).unwrap());
+
+        archive
+            .entries()
+            .unwrap()
+            .map(|e| {
+                let mut e = e.unwrap();
+                let mut content = Vec::new();
+                e.read_to_end(&mut content).unwrap();
+
+                (e.path().unwrap().to_string_lossy().to_string(), content)
+            })
+            .collect()
+    }
+
+    fn blob<'a>(image: &'a HashMap<String, Vec<u8>>, digest: &Value) -> &'a [u8] {
+        let digest = digest.as_str().unwrap().strip_prefix("sha256:").unwrap();
+        let content = &image[&format!("blobs/sha256/{digest}")];
+        assert_eq!(digest, calculate_sha256(content));
+
+        content
+    }
+
+    fn layer_paths(layer: &[u8]) -> Vec<String> {
+        let mut archive = tar::Archive::new(GzDecoder::new(layer));
+
+        archive
+            .entries()
+            .unwrap()
+            .map(|e| e.unwrap().path().unwrap().to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn export_layer_per_paket() -> Result<(), Box<dyn std::error::Error>> {
+        let folder = tempfile::tempdir()?;
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket_with_assets"),
+            folder.path(),
+        )?;
+        let installer = bootstrap(&folder.path().join("rootfs"), &[paket])?;
+
+        let image_path = folder.path().join("image.tar");
+        export_oci_image(&installer, &image_path, &OciExportOptions::default())?;
+
+        let image = read_image(&image_path);
+        assert!(image.contains_key("oci-layout"));
+
+        let index: Value = serde_json::from_slice(&image["index.json"])?;
+        let manifest_descriptor = &index["manifests"][0];
+        assert_eq!(
+            "0.1.0",
+            manifest_descriptor["annotations"]["org.opencontainers.image.ref.name"]
+        );
+
+        let manifest: Value = serde_json::from_slice(blob(&image, &manifest_descriptor["digest"]))?;
+        let config: Value = serde_json::from_slice(blob(&image, &manifest["config"]["digest"]))?;
+        let labels = &config["config"]["Labels"];
+        assert_eq!(
+            "my-helloworld-app",
+            labels["org.opencontainers.image.title"]
+        );
+        assert_eq!("MIT", labels["org.opencontainers.image.licenses"]);
+        assert_eq!("/usr/bin/helloworld", config["config"]["Entrypoint"][0]);
+
+        // Base layer + a layer for the paket
+        let layers = manifest["layers"].as_array().unwrap();
+        assert_eq!(2, layers.len());
+        assert_eq!(2, config["rootfs"]["diff_ids"].as_array().unwrap().len());
+
+        let base_layer = layer_paths(blob(&image, &layers[0]["digest"]));
+        assert!(base_layer.iter().any(|p| p.starts_with("var/lib/paket")));
+        assert!(!base_layer.contains(&"usr/bin/helloworld".to_string()));
+
+        let paket_layer = layer_paths(blob(&image, &layers[1]["digest"]));
+        assert!(paket_layer.contains(&"usr/bin/helloworld".to_string()));
+        assert!(paket_layer.contains(&"usr/bin".to_string()));
+
+        // Squashed
+        let options = OciExportOptions {
+            layer_mode: LayerMode::Squashed,
+            tag: Some("latest".to_string()),
+            ..Default::default()
+        };
+        export_oci_image(&installer, &image_path, &options)?;
+
+        let image = read_image(&image_path);
+        let index: Value = serde_json::from_slice(&image["index.json"])?;
+        let manifest: Value =
+            serde_json::from_slice(blob(&image, &index["manifests"][0]["digest"]))?;
+        let layers = manifest["layers"].as_array().unwrap();
+        assert_eq!(1, layers.len());
+        assert!(layer_paths(blob(&image, &layers[0]["digest"]))
+            .contains(&"usr/bin/helloworld".to_string()));
+
+        Ok(())
+    }
+}
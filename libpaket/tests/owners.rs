@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::*;
+    use libpaket::owners::*;
+
+    #[test]
+    fn find_owners_of_installed_files() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            pakets.path(),
+        )?;
+
+        installer.install_paket(Path::new(&paket))?;
+
+        assert_eq!(
+            vec![FileOwner {
+                path: "/usr/bin/helloworld".to_string(),
+                pakets: vec!["helloworld".to_string()],
+            }],
+            find_owners(&installer, "/usr/bin/helloworld")?
+        );
+        assert_eq!(
+            vec![FileOwner {
+                path: "/usr/share/icons/hicolor/scalable/apps/helloworld.svg".to_string(),
+                pakets: vec!["helloworld".to_string()],
+            }],
+            find_owners(&installer, "/usr/share/icons/**/*.svg")?
+        );
+        // `*` doesn't match folders
+        assert!(find_owners(&installer, "/usr/*")?.is_empty());
+        assert!(find_owners(&installer, "/usr/bin/not-installed")?.is_empty());
+        assert!(find_owners(&installer, "/usr/[bin").is_err());
+
+        // Index is created again from the installed records
+        fs::remove_file(installer.get_state_dir().join("files.toml"))?;
+        assert_eq!(1, find_owners(&installer, "/usr/bin/helloworld")?.len());
+
+        installer.remove_paket("helloworld")?;
+        assert!(find_owners(&installer, "/usr/bin/helloworld")?.is_empty());
+        assert!(read_index(&installer)?.files.is_empty());
+
+        Ok(())
+    }
+}
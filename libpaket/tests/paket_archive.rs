@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs::File;
+    use std::io::{Cursor, Read};
+    use std::path::Path;
+
+    use libpaket::archive::*;
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::sha256::calculate_sha256;
+    use libpaket::PaketError;
+
+    /// Copy the entries of a .paket file into a new tar archive, changing `SHA256SUM` and skipping `skip`.
+    fn rebuild_paket(paket_path: &Path, sha256sum: Option<&str>, skip: &str) -> Vec<u8> {
+        let mut ar = tar::Archive::new(File::open(paket_path).unwrap());
+        let mut builder = tar::Builder::new(Vec::new());
+
+        for entry in ar.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path = entry.path().unwrap().to_string_lossy().to_string();
+            if path == skip {
+                continue;
+            }
+
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content).unwrap();
+            if let (Some(s), "SHA256SUM") = (sha256sum, path.as_str()) {
+                content = s.as_bytes().to_vec();
+            }
+
+            let mut header = entry.header().clone();
+            header.set_size(content.len() as u64);
+            header.set_cksum();
+            builder.append(&header, content.as_slice()).unwrap();
+        }
+
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn read_paket_archive() -> Result<(), Box<dyn std::error::Error>> {
+        let folder = tempfile::tempdir()?;
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            folder.path(),
+        )?;
+
+        let mut archive = PaketArchive::open(&paket)?;
+        assert_eq!("helloworld", archive.get_config().package.name);
+        assert!(archive.get_paket_toml().contains("helloworld"));
+        assert_eq!(64, archive.get_sha256sum().len());
+        archive.verify_checksum()?;
+
+        let entries: Vec<PayloadEntry> = archive
+            .payload()?
+            .entries()?
+            .collect::<libpaket::Result<_>>()?;
+        let executable = entries
+            .iter()
+            .find(|e| e.path == Path::new("usr/bin/helloworld"))
+            .unwrap();
+        assert_eq!(EntryKind::File, executable.kind);
+        assert_eq!(
+            std::fs::read("./example_pakets/application_paket/helloworld")?,
+            executable.content
+        );
+
+        // Entries can be read again
+        assert_eq!(entries.len(), archive.payload()?.entries()?.count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_paket_archives() -> Result<(), Box<dyn std::error::Error>> {
+        let folder = tempfile::tempdir()?;
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            folder.path(),
+        )?;
+
+        let changed_checksum = rebuild_paket(&paket, Some(&calculate_sha256(b"other")), "");
+        let mut archive = PaketArchive::from_reader(Cursor::new(changed_checksum))?;
+        assert!(matches!(
+            archive.verify_checksum(),
+            Err(PaketError::ChecksumMismatch(_))
+        ));
+
+        let without_data = rebuild_paket(&paket, None, "data.tar.gz");
+        assert!(matches!(
+            PaketArchive::from_reader(Cursor::new(without_data)),
+            Err(PaketError::InvalidPaketArchive(_))
+        ));
+
+        Ok(())
+    }
+}
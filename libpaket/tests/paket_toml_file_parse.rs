@@ -7,6 +7,7 @@ mod tests {
     use libpaket::toml_structs::paket_toml::*;
 
     #[test]
+    #[allow(clippy::useless_format)]
     fn deserialize_basic_toml() {
         let read_config: Config =
             read_config_from_toml(Path::new(format!("./example_pakets/basic.toml").as_str()))
@@ -25,6 +26,8 @@ mod tests {
                 source_repository: None,
                 keywords: None,
                 categories: None,
+                config_files: None,
+                replaces: None,
             },
             dependencies: None,
             application: Some(ApplicationInformation {
@@ -34,12 +37,15 @@ mod tests {
                 desktop_file: None,
             }),
             script: None,
+            scripts: None,
+            triggers: None,
         };
 
         assert_eq!(read_config, expected_config);
     }
 
     #[test]
+    #[allow(clippy::useless_format)]
     fn deserialize_application_full_toml() {
         let read_config: Config = read_config_from_toml(Path::new(
             format!("./example_pakets/application_full.toml").as_str(),
@@ -82,11 +88,14 @@ mod tests {
                     String::from("here"),
                 ]),
                 categories: Some(vec![String::from("Game"), String::from("Education")]),
+                config_files: None,
+                replaces: None,
             },
             dependencies: Some(Dependencies {
                 application: Some(application_dependency_list),
                 library: Some(library_dependency_list),
                 development: None,
+                recommended: None,
             }),
             application: Some(ApplicationInformation {
                 executable: String::from("myapp"),
@@ -95,6 +104,8 @@ mod tests {
                 desktop_file: None,
             }),
             script: None,
+            scripts: None,
+            triggers: None,
         };
 
         assert_eq!(read_config, expected_config);
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::{Path, PathBuf};
+
+    use libpaket::placeholder::*;
+
+    #[test]
+    fn split_user_home_placeholder() {
+        assert_eq!(
+            split_placeholder(Path::new("$USER_HOME/.fonts/hack/Hack-Bold.ttf")),
+            Some((
+                Placeholder::UserHome,
+                PathBuf::from(".fonts/hack/Hack-Bold.ttf")
+            ))
+        );
+        assert_eq!(
+            split_placeholder(Path::new("./$XDG_CONFIG_HOME/app/settings.ini")),
+            Some((
+                Placeholder::XdgConfigHome,
+                PathBuf::from("app/settings.ini")
+            ))
+        );
+        assert_eq!(
+            split_placeholder(Path::new("usr/share/fonts/Hack-Bold.ttf")),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_passwd_content() {
+        let users = parse_passwd(
+            "root:x:0:0:root:/root:/bin/bash\n\
+             # comment\n\
+             emin:x:1000:1000:Emin,,,:/home/emin:/bin/bash\n\
+             broken-line\n",
+        );
+
+        assert_eq!(
+            users,
+            vec![
+                User {
+                    name: String::from("root"),
+                    uid: 0,
+                    gid: 0,
+                    home: PathBuf::from("/root"),
+                },
+                User {
+                    name: String::from("emin"),
+                    uid: 1000,
+                    gid: 1000,
+                    home: PathBuf::from("/home/emin"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn all_users_policy() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        std::fs::create_dir_all(root.path().join("etc"))?;
+        std::fs::create_dir_all(root.path().join("home/emin"))?;
+        std::fs::write(
+            root.path().join("etc/passwd"),
+            "root:x:0:0:root:/root:/bin/bash\n\
+             emin:x:1000:1000:Emin:/home/emin:/bin/bash\n\
+             nohome:x:1001:1001:No Home:/home/nohome:/bin/bash\n\
+             nobody:x:65534:65534:nobody:/nonexistent:/usr/sbin/nologin\n",
+        )?;
+
+        let targets = home_targets(root.path(), UserHomePolicy::AllUsers)?;
+        let homes: Vec<&Path> = targets.iter().map(|t| t.home.as_path()).collect();
+
+        assert_eq!(homes, vec![Path::new("/home/emin"), Path::new("/etc/skel")]);
+        assert_eq!(targets[0].user.as_ref().unwrap().name, "emin");
+        assert_eq!(targets[1].user, None);
+
+        Ok(())
+    }
+}
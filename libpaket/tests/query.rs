@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::*;
+    use libpaket::query::*;
+    use libpaket::repos::*;
+    use libpaket::repository::{update_index, POOL_FOLDER};
+    use libpaket::toml_structs::paket_toml::PackageType;
+    use libpaket::PaketError;
+    use semver::Version;
+
+    fn names(pakets: &[InstalledPaket]) -> Vec<&str> {
+        pakets
+            .iter()
+            .map(|p| p.config.package.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn list_installed_pakets_with_filters() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let (application, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            pakets.path(),
+        )?;
+        let (configuration, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/configuration_paket"),
+            pakets.path(),
+        )?;
+
+        installer.install_paket(Path::new(&application))?;
+        installer
+            .clone()
+            .with_options(InstallOptions {
+                install_as_dependency: true,
+                ..Default::default()
+            })
+            .install_paket(Path::new(&configuration))?;
+
+        let all = list_pakets(&installer, &ListFilter::default())?;
+        assert_eq!(vec!["font-hack", "helloworld"], names(&all));
+        assert!(all.iter().all(|p| p.size > 0));
+
+        let filter = ListFilter {
+            package_type: Some(PackageType::Application),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["helloworld"],
+            names(&list_pakets(&installer, &filter)?)
+        );
+
+        let filter = ListFilter {
+            name_pattern: Some(String::from("font-*")),
+            ..Default::default()
+        };
+        assert_eq!(vec!["font-hack"], names(&list_pakets(&installer, &filter)?));
+
+        let filter = ListFilter {
+            name_pattern: Some(String::from("world")),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["helloworld"],
+            names(&list_pakets(&installer, &filter)?)
+        );
+
+        let filter = ListFilter {
+            dependency: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(vec!["font-hack"], names(&list_pakets(&installer, &filter)?));
+
+        let filter = ListFilter {
+            upgradable: Some(HashMap::from([
+                (String::from("helloworld"), Version::new(9, 0, 0)),
+                (String::from("font-hack"), Version::new(1, 0, 0)),
+            ])),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["helloworld"],
+            names(&list_pakets(&installer, &filter)?)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_installed_paket_info() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let (application, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            pakets.path(),
+        )?;
+
+        installer.install_paket(Path::new(&application))?;
+
+        let paket = find_installed_paket(&installer, "helloworld")?;
+        assert_eq!("0.1.0", paket.config.package.version);
+        assert!(!paket.info.dependency);
+        assert!(paket.info.install_time.is_some());
+        assert_eq!(
+            installer.installed_files("helloworld")?.len(),
+            paket.info.files.len()
+        );
+
+        assert!(find_installed_paket(&installer, "not-installed").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_paket_in_repositories() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let pool = repository.path().join(POOL_FOLDER);
+        fs::create_dir_all(&pool)?;
+        create_paket_from_toml(Path::new("./example_pakets/application_paket"), &pool)?;
+        update_index(repository.path(), None)?;
+
+        let root = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let config_dir = root.path().join(REPOS_CONFIG_FOLDER);
+        fs::create_dir_all(&config_dir)?;
+        fs::write(
+            config_dir.join("main.toml"),
+            format!(
+                "url = \"file://{}\"\nallow_unsigned = true\n",
+                repository.path().display()
+            ),
+        )?;
+        for repository in read_repositories(&installer)? {
+            update_repository(&installer, &repository)?;
+        }
+
+        // The paket is not installed but it is in the repository
+        assert!(find_installed_paket(&installer, "helloworld").is_err());
+        let paket = find_repository_paket(&installer, "helloworld")?;
+        assert_eq!("0.1.0", paket.entry.version);
+        assert_eq!("main", paket.repository);
+
+        assert!(matches!(
+            find_repository_paket(&installer, "not-exists"),
+            Err(PaketError::PaketNotFound(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::Installer;
+    use libpaket::remote::*;
+    use libpaket::repository::*;
+    use libpaket::signature::generate_key_pair;
+    use libpaket::toml_structs::index_toml::RepositoryIndex;
+    use libpaket::PaketError;
+    use semver::Version;
+
+    #[test]
+    fn download_and_install_from_file_repository() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let pool = repository.path().join(POOL_FOLDER);
+        fs::create_dir_all(&pool)?;
+
+        let (secret_key, public_key) = generate_key_pair()?;
+
+        create_paket_from_toml(Path::new("./example_pakets/application_paket"), &pool)?;
+        update_index(repository.path(), Some(&secret_key))?;
+
+        let url = format!("file://{}", repository.path().display());
+        let repositories = vec![RemoteRepository::open(&url, Some(&public_key), false)?];
+
+        // The index must be signed with the trusted key, or unsigned repositories must be allowed
+        let (_other_secret_key, other_public_key) = generate_key_pair()?;
+        assert!(matches!(
+            RemoteRepository::open(&url, Some(&other_public_key), false),
+            Err(PaketError::InvalidSignature(_))
+        ));
+        assert!(matches!(
+            RemoteRepository::open(&url, None, false),
+            Err(PaketError::UnsignedRepository(_))
+        ));
+        assert!(RemoteRepository::open(&url, None, true).is_ok());
+
+        let pakets = resolve_pakets(
+            &repositories,
+            &[String::from("helloworld")],
+            &HashMap::new(),
+            true,
+        )?;
+        assert_eq!(1, pakets.len());
+        assert_eq!(format!("{url}/pool/helloworld_0.1.0.paket"), pakets[0].url);
+
+        let root = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let cache_dir = installer.get_state_dir().join(CACHE_FOLDER);
+
+        let paket_path = download_paket(&pakets[0], &cache_dir)?;
+        assert_eq!(
+            cache_dir
+                .join("helloworld/0.1.0")
+                .join(&pakets[0].entry.sha256)
+                .join("helloworld_0.1.0.paket"),
+            paket_path
+        );
+        installer.install_paket_files(std::slice::from_ref(&paket_path))?;
+        assert!(root.path().join("usr/bin/helloworld").exists());
+
+        // A changed file in the repository is not accepted
+        let mut changed = pakets[0].clone();
+        changed.entry.sha256 = "0".repeat(64);
+        let changed_path = libpaket::cache::paket_path(&cache_dir, &changed.entry)?;
+        fs::remove_file(&paket_path)?;
+        assert!(matches!(
+            download_paket(&changed, &cache_dir),
+            Err(PaketError::ChecksumMismatch(_))
+        ));
+        assert!(!changed_path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn resolve_pakets_with_dependencies() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let (application, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            repository.path(),
+        )?;
+
+        let helloworld = index_entry(repository.path(), &application)?;
+
+        let mut library = helloworld.clone();
+        library.name = String::from("libhello");
+        library.version = String::from("1.2.0");
+        library.filename = String::from("pool/libhello_1.2.0.paket");
+
+        let mut extra = library.clone();
+        extra.name = String::from("hello-extra");
+        extra.filename = String::from("pool/hello-extra_1.2.0.paket");
+
+        let mut application = helloworld.clone();
+        application
+            .dependencies
+            .insert(String::from("libhello"), String::from("^1.1"));
+        application
+            .recommends
+            .insert(String::from("hello-extra"), String::from("*"));
+        application
+            .recommends
+            .insert(String::from("not-exists"), String::from("*"));
+
+        let first = RemoteRepository::from_index(
+            "http://localhost:8080",
+            RepositoryIndex {
+                pakets: vec![application],
+            },
+        );
+        let second = RemoteRepository::from_index(
+            "http://localhost:8081/",
+            RepositoryIndex {
+                pakets: vec![helloworld, library, extra],
+            },
+        );
+        let repositories = vec![first, second];
+        let names = [String::from("helloworld")];
+
+        let pakets = resolve_pakets(&repositories, &names, &HashMap::new(), true)?;
+        let resolved: Vec<(&str, &str)> = pakets
+            .iter()
+            .map(|p| (p.entry.name.as_str(), p.url.as_str()))
+            .collect();
+        assert_eq!(
+            vec![
+                ("helloworld", "http://localhost:8080/helloworld_0.1.0.paket"),
+                (
+                    "libhello",
+                    "http://localhost:8081/pool/libhello_1.2.0.paket"
+                ),
+                (
+                    "hello-extra",
+                    "http://localhost:8081/pool/hello-extra_1.2.0.paket"
+                )
+            ],
+            resolved
+        );
+
+        // Recommended pakets are optional
+        assert_eq!(
+            2,
+            resolve_pakets(&repositories, &names, &HashMap::new(), false)?.len()
+        );
+
+        // Installed dependencies are not downloaded again
+        let installed = HashMap::from([(String::from("libhello"), Version::new(1, 5, 0))]);
+        assert_eq!(
+            1,
+            resolve_pakets(&repositories, &names, &installed, false)?.len()
+        );
+
+        assert!(matches!(
+            resolve_pakets(
+                &repositories,
+                &[String::from("not-exists")],
+                &HashMap::new(),
+                true
+            ),
+            Err(PaketError::PaketNotFound(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::Installer;
+    use libpaket::remote::resolve_pakets;
+    use libpaket::repos::*;
+    use libpaket::repository::*;
+    use libpaket::signature::generate_key_pair;
+    use libpaket::PaketError;
+
+    fn paket_names(installer: &Installer) -> Vec<String> {
+        let repositories = stored_repositories(installer).unwrap();
+        repositories[0]
+            .get_index()
+            .pakets
+            .iter()
+            .map(|p| p.name.clone())
+            .collect()
+    }
+
+    #[test]
+    fn update_signed_repository() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let pool = repository.path().join(POOL_FOLDER);
+        fs::create_dir_all(&pool)?;
+        let (secret_key, public_key) = generate_key_pair()?;
+
+        create_paket_from_toml(Path::new("./example_pakets/application_paket"), &pool)?;
+        update_index(repository.path(), Some(&secret_key))?;
+
+        let root = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let config_dir = root.path().join(REPOS_CONFIG_FOLDER);
+        fs::create_dir_all(&config_dir)?;
+        fs::write(
+            config_dir.join("main.toml"),
+            format!(
+                "url = \"file://{}\"\ntrusted_key = \"{public_key}\"\n",
+                repository.path().display()
+            ),
+        )?;
+
+        let repositories = read_repositories(&installer)?;
+        assert_eq!(1, repositories.len());
+        assert_eq!("main", repositories[0].name);
+        assert!(matches!(
+            stored_repositories(&installer),
+            Err(PaketError::RepositoryNotUpdated(_))
+        ));
+
+        assert_eq!(
+            UpdateStatus::Updated,
+            update_repository(&installer, &repositories[0])?
+        );
+        assert_eq!(
+            UpdateStatus::NotModified,
+            update_repository(&installer, &repositories[0])?
+        );
+        assert_eq!(vec!["helloworld"], paket_names(&installer));
+
+        // A corrupt index doesn't replace the last good one
+        let index_path = repository.path().join(INDEX_FILE);
+        let index = fs::read(&index_path)?;
+        fs::write(&index_path, "corrupt")?;
+        assert!(update_repository(&installer, &repositories[0]).is_err());
+        assert_eq!(vec!["helloworld"], paket_names(&installer));
+        fs::write(&index_path, index)?;
+
+        // An index signed with another key isn't accepted
+        create_paket_from_toml(Path::new("./example_pakets/configuration_paket"), &pool)?;
+        let (other_secret_key, _other_public_key) = generate_key_pair()?;
+        update_index(repository.path(), Some(&other_secret_key))?;
+
+        assert!(matches!(
+            update_repository(&installer, &repositories[0]),
+            Err(PaketError::InvalidSignature(_))
+        ));
+        assert_eq!(vec!["helloworld"], paket_names(&installer));
+
+        write_index(
+            repository.path(),
+            &read_index(repository.path())?,
+            Some(&secret_key),
+        )?;
+        assert_eq!(
+            UpdateStatus::Updated,
+            update_repository(&installer, &repositories[0])?
+        );
+        assert_eq!(vec!["font-hack", "helloworld"], paket_names(&installer));
+        assert!(!root
+            .path()
+            .join("var/lib/paket/repos/main/index.toml.gz.part")
+            .exists());
+
+        // Repositories without a trusted key must be allowed explicitly
+        fs::write(
+            config_dir.join("unsigned.toml"),
+            format!("url = \"file://{}\"\n", repository.path().display()),
+        )?;
+        let repositories = read_repositories(&installer)?;
+        assert!(matches!(
+            update_repository(&installer, &repositories[1]),
+            Err(PaketError::UnsignedRepository(_))
+        ));
+
+        let unsigned = root.path().join("var/lib/paket/repos/unsigned");
+        fs::create_dir_all(&unsigned)?;
+        fs::copy(
+            root.path()
+                .join("var/lib/paket/repos/main")
+                .join(INDEX_FILE),
+            unsigned.join(INDEX_FILE),
+        )?;
+        assert!(matches!(
+            stored_repositories(&installer),
+            Err(PaketError::UnsignedRepository(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn choose_repository_by_priority_and_pins() -> Result<(), Box<dyn std::error::Error>> {
+        // One repository with stable and testing channels
+        let repository = tempfile::tempdir()?;
+        let configuration = "font-hack_3.3.0.paket";
+        for channel in ["stable", "testing"] {
+            let pool = repository.path().join(channel).join(POOL_FOLDER);
+            fs::create_dir_all(&pool)?;
+            create_paket_from_toml(Path::new("./example_pakets/application_paket"), &pool)?;
+            create_paket_from_toml(Path::new("./example_pakets/configuration_paket"), &pool)?;
+            update_index(&repository.path().join(channel), None)?;
+        }
+
+        let root = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let config_dir = root.path().join(REPOS_CONFIG_FOLDER);
+        fs::create_dir_all(&config_dir)?;
+        let url = format!("file://{}", repository.path().display());
+        fs::write(
+            config_dir.join("stable.toml"),
+            format!(
+                "url = \"{url}\"\nchannel = \"stable\"\nallow_unsigned = true\npriority = 10\npinned = [\"font-hack\"]\n"
+            ),
+        )?;
+        fs::write(
+            config_dir.join("testing.toml"),
+            format!(
+                "url = \"{url}/\"\nchannel = \"testing\"\nallow_unsigned = true\npriority = 100\n"
+            ),
+        )?;
+        fs::write(
+            config_dir.join("old.toml"),
+            "url = \"file:///not-exists\"\nenabled = false\npriority = 1000\n",
+        )?;
+
+        for repository in read_repositories(&installer)?
+            .iter()
+            .filter(|r| r.config.enabled)
+        {
+            update_repository(&installer, repository)?;
+        }
+
+        let repositories = stored_repositories(&installer)?;
+        let names: Vec<&str> = repositories.iter().map(|r| r.get_name()).collect();
+        assert_eq!(vec!["testing", "stable"], names);
+        assert_eq!(format!("{url}/testing"), repositories[0].get_url());
+
+        let pakets = resolve_pakets(
+            &repositories,
+            &[String::from("helloworld"), String::from("font-hack")],
+            &HashMap::new(),
+            true,
+        )?;
+        let chosen: Vec<(&str, &str)> = pakets
+            .iter()
+            .map(|p| (p.entry.name.as_str(), p.repository.as_str()))
+            .collect();
+        assert_eq!(
+            vec![("helloworld", "testing"), ("font-hack", "stable")],
+            chosen
+        );
+        assert_eq!(format!("{url}/stable/pool/{configuration}"), pakets[1].url);
+
+        Ok(())
+    }
+}
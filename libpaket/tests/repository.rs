@@ -0,0 +1,118 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::repository::*;
+    use libpaket::sha256::calculate_sha256;
+    use libpaket::signature::*;
+    use libpaket::toml_structs::index_toml::RepositoryIndex;
+    use libpaket::toml_structs::paket_toml::PackageType;
+
+    #[test]
+    fn repository_index_entries() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let pool = repository.path().join(POOL_FOLDER);
+        fs::create_dir_all(&pool)?;
+
+        // A paket outside of the repository folder can't be in the index
+        let outside = tempfile::tempdir()?;
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            outside.path(),
+        )?;
+        let paket_path = pool.join("helloworld_0.1.0.paket");
+        fs::copy(&paket, &paket_path)?;
+
+        let entry = index_entry(repository.path(), &paket_path)?;
+        assert_eq!("helloworld", entry.name);
+        assert_eq!("0.1.0", entry.version);
+        assert_eq!(PackageType::Application, entry.package_type);
+        assert_eq!("pool/helloworld_0.1.0.paket", entry.filename);
+        assert_eq!(fs::metadata(&paket_path)?.len(), entry.size);
+        assert_eq!(calculate_sha256(&fs::read(&paket_path)?), entry.sha256);
+        assert!(index_entry(repository.path(), &paket).is_err());
+
+        let mut newer = entry.clone();
+        newer.version = String::from("0.10.0");
+        let index = RepositoryIndex {
+            pakets: vec![entry.clone(), newer],
+        };
+        write_index(repository.path(), &index, None)?;
+
+        let read = read_index(repository.path())?;
+        assert_eq!(index, read);
+        assert_eq!("0.10.0", read.find("helloworld").unwrap().version);
+        assert!(read.find("not-exists").is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_signed_index_incrementally() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let pool = repository.path().join(POOL_FOLDER);
+        fs::create_dir_all(&pool)?;
+        let (secret_key, public_key) = generate_key_pair()?;
+
+        let pakets = tempfile::tempdir()?;
+        create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            pakets.path(),
+        )?;
+        create_paket_from_toml(
+            Path::new("./example_pakets/configuration_paket"),
+            pakets.path(),
+        )?;
+        let application = "helloworld_0.1.0.paket";
+        let configuration = "font-hack_3.3.0.paket";
+        fs::copy(pakets.path().join(application), pool.join(application))?;
+
+        let changes = update_index(repository.path(), Some(&secret_key))?;
+        assert_eq!(vec![format!("pool/{application}")], changes.added);
+
+        let compressed_index = fs::read(repository.path().join(INDEX_FILE))?;
+        let signature = fs::read_to_string(repository.path().join(SIGNATURE_FILE))?;
+        verify(&compressed_index, &signature, &public_key)?;
+        assert_eq!(1, parse_index(&compressed_index)?.pakets.len());
+
+        // Nothing is changed, the index is kept as is
+        let changes = update_index(repository.path(), Some(&secret_key))?;
+        assert!(changes.is_empty());
+        assert_eq!(1, changes.unchanged);
+        assert_eq!(
+            compressed_index,
+            fs::read(repository.path().join(INDEX_FILE))?
+        );
+
+        // Only the new paket is added, the removed paket is removed
+        fs::copy(pakets.path().join(configuration), pool.join(configuration))?;
+        fs::remove_file(pool.join(application))?;
+        let changes = update_index(repository.path(), Some(&secret_key))?;
+        assert_eq!(
+            IndexChanges {
+                added: vec![format!("pool/{configuration}")],
+                updated: vec![],
+                removed: vec![format!("pool/{application}")],
+                unchanged: 0,
+            },
+            changes
+        );
+
+        let compressed_index = fs::read(repository.path().join(INDEX_FILE))?;
+        let signature = fs::read_to_string(repository.path().join(SIGNATURE_FILE))?;
+        verify(&compressed_index, &signature, &public_key)?;
+        assert!(read_index(repository.path())?.find("font-hack").is_some());
+
+        // The index and its signature are replaced with temporary files which are not left behind
+        let mut files: Vec<String> = fs::read_dir(repository.path())?
+            .map(|e| Ok(e?.file_name().to_string_lossy().to_string()))
+            .collect::<std::io::Result<_>>()?;
+        files.sort();
+        assert_eq!(vec![INDEX_FILE, SIGNATURE_FILE, POOL_FOLDER], files);
+
+        Ok(())
+    }
+}
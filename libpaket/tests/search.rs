@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::Installer;
+    use libpaket::remote::RemoteRepository;
+    use libpaket::repository::index_entry;
+    use libpaket::search::*;
+    use libpaket::toml_structs::index_toml::RepositoryIndex;
+    use libpaket::toml_structs::paket_toml::PackageType;
+
+    fn ranks(results: &[SearchResult]) -> Vec<(&str, MatchRank)> {
+        results.iter().map(|r| (r.name.as_str(), r.rank)).collect()
+    }
+
+    #[test]
+    fn fold_turkish_letters() {
+        assert_eq!("istanbul işik", fold_case("İSTANBUL ışık"));
+        assert_eq!(fold_case("Çağrı Öğün"), fold_case("ÇAĞRI ÖĞÜN"));
+    }
+
+    #[test]
+    fn search_repository_and_installed_pakets() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let mut index = RepositoryIndex::default();
+        for folder in ["application_paket", "configuration_paket_with_user_home"] {
+            let (paket, _file) = create_paket_from_toml(
+                &Path::new("./example_pakets").join(folder),
+                repository.path(),
+            )?;
+            index.pakets.push(index_entry(repository.path(), &paket)?);
+        }
+        let repositories = vec![RemoteRepository::from_index("http://localhost:8080", index)];
+
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let (configuration, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/configuration_paket"),
+            pakets.path(),
+        )?;
+        installer.install_paket(&configuration)?;
+
+        let search = |terms: &[&str], options: &SearchOptions| {
+            let terms: Vec<String> = terms.iter().map(|t| t.to_string()).collect();
+            search_pakets(&installer, &repositories, &terms, options)
+        };
+        let all = SearchOptions::default();
+
+        let results = search(&["font-hack"], &all)?;
+        assert_eq!(
+            vec![
+                ("font-hack", MatchRank::ExactName),
+                ("font-hack-user-only", MatchRank::NamePrefix)
+            ],
+            ranks(&results)
+        );
+        assert_eq!(Some(String::from("3.3.0")), results[0].installed_version);
+        assert_eq!(None, results[0].repository);
+        assert_eq!(
+            Some(String::from("http://localhost:8080")),
+            results[1].repository
+        );
+
+        assert_eq!(
+            vec![("helloworld", MatchRank::Keyword)],
+            ranks(&search(&["PACKAGE"], &all)?)
+        );
+        assert_eq!(
+            vec![("helloworld", MatchRank::Category)],
+            ranks(&search(&["EDUCATİON"], &all)?)
+        );
+        assert_eq!(
+            vec![
+                ("font-hack", MatchRank::Description),
+                ("font-hack-user-only", MatchRank::Description)
+            ],
+            ranks(&search(&["source", "code"], &all)?)
+        );
+        assert!(search(&["source", "helloworld"], &all)?.is_empty());
+
+        let installed = SearchOptions {
+            installed_only: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![("font-hack", MatchRank::NamePrefix)],
+            ranks(&search(&["font"], &installed)?)
+        );
+
+        let applications = SearchOptions {
+            package_type: Some(PackageType::Application),
+            ..Default::default()
+        };
+        assert!(search(&["font"], &applications)?.is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::common::build_paket;
+    use libpaket::install::*;
+    use libpaket::triggers::*;
+
+    /// Build a configuration paket which installs `/usr/share/trigger-test/<name>.txt` and has the `extra_toml` in Paket.toml
+    fn build_trigger_paket(folder: &Path, name: &str, extra_toml: &str) -> PathBuf {
+        build_paket(
+            folder,
+            name,
+            "1.0.0",
+            extra_toml,
+            &[(&format!("usr/share/trigger-test/{name}.txt"), name)],
+        )
+    }
+
+    #[test]
+    fn trigger_runs_once_per_transaction() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let provider = build_trigger_paket(
+            pakets.path(),
+            "trigger-provider",
+            r#"
+[[triggers]]
+name = "trigger-test"
+paths = ["/usr/share/trigger-test"]
+command = "echo $PAKET_PREFIX >> \"$PAKET_ROOT/trigger.log\""
+"#,
+        );
+        let first = build_trigger_paket(pakets.path(), "trigger-first", "");
+        let second = build_trigger_paket(pakets.path(), "trigger-second", "");
+
+        installer.install_paket_files(&[provider, first, second])?;
+
+        let log_path = root.path().join("trigger.log");
+        let prefix = fs::canonicalize(root.path())?.join("usr");
+        assert_eq!(
+            format!("{}\n", prefix.display()),
+            fs::read_to_string(&log_path)?
+        );
+
+        installer.remove_pakets(&["trigger-first".to_string(), "trigger-second".to_string()])?;
+        assert_eq!(2, fs::read_to_string(&log_path)?.lines().count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn builtin_triggers_by_path() {
+        let root = tempfile::tempdir().unwrap();
+        let installer = Installer::new(root.path());
+        let triggers = builtin_triggers(InstallTarget::System);
+
+        let triggered: Vec<&str> = find_triggered(
+            &installer,
+            &triggers,
+            &[
+                PathBuf::from("/usr/share/applications/helloworld.desktop"),
+                PathBuf::from("/usr/share/icons/hicolor/scalable/apps/helloworld.svg"),
+                PathBuf::from("/usr/share/applications/other.desktop"),
+                PathBuf::from("/etc/skel/.fonts/hack/Hack-Regular.ttf"),
+            ],
+        )
+        .into_iter()
+        .map(|t| t.name.as_str())
+        .collect();
+
+        assert_eq!(
+            vec![
+                "gtk-update-icon-cache",
+                "update-desktop-database",
+                "fc-cache"
+            ],
+            triggered
+        );
+    }
+}
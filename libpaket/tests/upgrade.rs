@@ -0,0 +1,134 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use crate::common::build_paket;
+    use libpaket::install::Installer;
+    use libpaket::remote::{download_pakets, RemotePaket, RemoteRepository};
+    use libpaket::repository::*;
+    use libpaket::upgrade::*;
+    use semver::Version;
+
+    /// Build a configuration paket into the pool of the repository and update its index.
+    fn add_paket(repository: &Path, name: &str, version: &str, dependencies: &str) -> PathBuf {
+        let paket_path = build_paket(
+            &repository.join("src"),
+            name,
+            version,
+            &format!("\n[dependencies.application]\n{dependencies}"),
+            &[(&format!("usr/share/{name}/version"), version)],
+        );
+
+        let pool = repository.join(POOL_FOLDER);
+        fs::create_dir_all(&pool).unwrap();
+        let pool_path = pool.join(paket_path.file_name().unwrap());
+        fs::rename(&paket_path, &pool_path).unwrap();
+        update_index(repository, None).unwrap();
+
+        pool_path
+    }
+
+    fn open(repository: &Path) -> Vec<RemoteRepository> {
+        let url = format!("file://{}", repository.display());
+        vec![RemoteRepository::open(&url, None, true).unwrap()]
+    }
+
+    fn names(pakets: &[RemotePaket]) -> Vec<(&str, &str)> {
+        pakets
+            .iter()
+            .map(|p| (p.entry.name.as_str(), p.entry.version.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn upgrade_installed_pakets() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let root = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+
+        let app = add_paket(repository.path(), "upgrade-app", "1.0.0", "");
+        let user = add_paket(
+            repository.path(),
+            "upgrade-user",
+            "1.0.0",
+            "upgrade-app = \"^1\"",
+        );
+        installer.install_paket_files(&[app, user])?;
+
+        let plan = plan_upgrade(&installer, &open(repository.path()), true)?;
+        assert!(plan.is_empty());
+
+        // New version with a new dependency
+        add_paket(repository.path(), "upgrade-lib", "1.0.0", "");
+        add_paket(
+            repository.path(),
+            "upgrade-app",
+            "1.1.0",
+            "upgrade-lib = \"^1\"",
+        );
+
+        let plan = plan_upgrade(&installer, &open(repository.path()), true)?;
+        assert_eq!(1, plan.upgrades.len());
+        assert_eq!("1.0.0", plan.upgrades[0].installed_version.to_string());
+        assert_eq!(
+            vec![("upgrade-app", "1.1.0")],
+            names(&[plan.upgrades[0].paket.clone()])
+        );
+        assert_eq!(vec![("upgrade-lib", "1.0.0")], names(&plan.new_pakets));
+
+        let pakets: Vec<RemotePaket> = plan.pakets().cloned().collect();
+        let paket_files = download_pakets(&pakets, &root.path().join("cache"), 2)?;
+        let (upgrade_files, new_files) = paket_files.split_at(plan.upgrades.len());
+        installer.install_paket_files_with_dependencies(upgrade_files, new_files)?;
+
+        let versions = installer.installed_versions()?;
+        assert_eq!("1.1.0", versions["upgrade-app"].to_string());
+        assert_eq!("1.0.0", versions["upgrade-lib"].to_string());
+        assert!(!installer.installed_info("upgrade-app")?.dependency);
+        assert!(installer.installed_info("upgrade-lib")?.dependency);
+        assert_eq!(
+            "1.1.0",
+            fs::read_to_string(root.path().join("usr/share/upgrade-app/version"))?
+        );
+
+        // A new major version which breaks the installed pakets requiring the old one is held back
+        add_paket(repository.path(), "upgrade-app", "2.0.0", "");
+        let plan = plan_upgrade(&installer, &open(repository.path()), true)?;
+        assert!(plan.is_empty());
+        assert_eq!(
+            vec![HeldBackPaket {
+                name: String::from("upgrade-app"),
+                installed_version: Version::new(1, 1, 0),
+                available_version: String::from("2.0.0"),
+                required_by: String::from("upgrade-user (^1)"),
+            }],
+            plan.held_back
+        );
+
+        // The highest version which the installed pakets accept is chosen instead
+        add_paket(repository.path(), "upgrade-app", "1.2.0", "");
+        let plan = plan_upgrade(&installer, &open(repository.path()), true)?;
+        assert_eq!(
+            vec![("upgrade-app", "1.2.0")],
+            names(&[plan.upgrades[0].paket.clone()])
+        );
+        assert!(plan.held_back.is_empty());
+        let url = format!("file://{}", repository.path().display());
+        assert_eq!(
+            vec![OutdatedPaket {
+                name: String::from("upgrade-app"),
+                installed_version: String::from("1.1.0"),
+                available_version: String::from("2.0.0"),
+                repository: url.clone(),
+                url: format!("{url}/pool/upgrade-app_2.0.0.paket"),
+            }],
+            outdated_pakets(&installer, &open(repository.path()))?
+        );
+
+        Ok(())
+    }
+}
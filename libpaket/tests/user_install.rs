@@ -0,0 +1,88 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    use crate::common::build_paket;
+    use libpaket::install::*;
+    use libpaket::placeholder::Placeholder;
+    use libpaket::PaketError;
+
+    // Only one test in this file: it changes the environment of the whole test process.
+    #[test]
+    fn install_for_user() -> Result<(), Box<dyn std::error::Error>> {
+        let home = tempfile::tempdir()?;
+        let data_home = home.path().join("data");
+        let pakets = tempfile::tempdir()?;
+        std::env::set_var("HOME", home.path());
+        std::env::set_var("XDG_DATA_HOME", &data_home);
+        std::env::remove_var("XDG_CONFIG_HOME");
+
+        let installer = Installer::for_user()?;
+        assert_eq!(InstallTarget::User, installer.get_target());
+        assert_eq!(data_home.join("paket"), installer.get_state_dir());
+
+        let paket = build_paket(
+            pakets.path(),
+            "user-test",
+            "1.0.0",
+            "",
+            &[
+                ("usr/bin/user-test", "#!/bin/sh\n"),
+                ("usr/share/user-test/data.txt", "data"),
+                ("$XDG_CONFIG_HOME/user-test/app.conf", "config"),
+                ("$XDG_DATA_HOME/user-test/state.txt", "state"),
+            ],
+        );
+        installer.install_paket_files(&[paket])?;
+
+        assert!(home.path().join(".local/bin/user-test").exists());
+        assert_eq!(
+            "data",
+            fs::read_to_string(home.path().join(".local/share/user-test/data.txt"))?
+        );
+        assert_eq!(
+            "config",
+            fs::read_to_string(home.path().join(".config/user-test/app.conf"))?
+        );
+        assert_eq!(
+            "state",
+            fs::read_to_string(data_home.join("user-test/state.txt"))?
+        );
+        assert!(installer.installed_info("user-test").is_ok());
+        assert!(data_home.join("paket").is_dir());
+
+        // Files outside of /usr need root
+        let system_paket = build_paket(
+            pakets.path(),
+            "user-test-system",
+            "1.0.0",
+            "",
+            &[("etc/user-test.conf", "system")],
+        );
+        assert!(matches!(
+            installer.install_paket_files(&[system_paket]),
+            Err(PaketError::NotAllowedInUserMode(_))
+        ));
+        assert!(installer.installed_info("user-test-system").is_err());
+
+        installer.remove_pakets(&[String::from("user-test")])?;
+        assert!(!home.path().join(".local/bin/user-test").exists());
+
+        // $XDG_CONFIG_HOME is used when it is set, for the home folder of the current user only
+        let config_home = home.path().join("config");
+        std::env::set_var("XDG_CONFIG_HOME", &config_home);
+        assert_eq!(
+            config_home,
+            Placeholder::XdgConfigHome.path_in_home(home.path())
+        );
+        assert_eq!(
+            pakets.path().join(".config"),
+            Placeholder::XdgConfigHome.path_in_home(pakets.path())
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::*;
+    use libpaket::verify::*;
+
+    #[test]
+    fn verify_changed_files() -> Result<(), Box<dyn std::error::Error>> {
+        let root = tempfile::tempdir()?;
+        let pakets = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let (paket, _file) = create_paket_from_toml(
+            Path::new("./example_pakets/application_paket"),
+            pakets.path(),
+        )?;
+
+        installer.install_paket(Path::new(&paket))?;
+
+        let report = verify_paket(&installer, "helloworld")?;
+        assert!(report.is_ok());
+        assert_eq!(Vec::<FileReport>::new(), report.files);
+        assert!(report.checked_files > 0);
+
+        let executable = root.path().join("usr/bin/helloworld");
+        let mode = fs::metadata(&executable)?.permissions().mode() & 0o7777;
+        fs::write(&executable, "changed")?;
+        fs::set_permissions(&executable, fs::Permissions::from_mode(0o777))?;
+        fs::remove_file(
+            root.path()
+                .join("usr/share/icons/hicolor/scalable/apps/helloworld.svg"),
+        )?;
+
+        let report = verify_pakets(&installer, &[])?.remove(0);
+        assert!(!report.is_ok());
+        assert_eq!(
+            vec![
+                FileReport {
+                    path: "/usr/bin/helloworld".to_string(),
+                    config: false,
+                    problems: vec![
+                        FileProblem::ContentChanged,
+                        FileProblem::ModeChanged {
+                            expected: mode,
+                            actual: 0o777
+                        }
+                    ],
+                },
+                FileReport {
+                    path: "/usr/share/icons/hicolor/scalable/apps/helloworld.svg".to_string(),
+                    config: false,
+                    problems: vec![FileProblem::Missing],
+                },
+            ],
+            report.files
+        );
+
+        assert!(verify_paket(&installer, "not-installed").is_err());
+
+        Ok(())
+    }
+}
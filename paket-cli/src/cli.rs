@@ -1,4 +1,83 @@
 use clap::{Arg, ArgAction, Command};
+use libpaket::toml_structs::paket_toml::PackageType;
+
+/// `--user` flag of the commands which operate on the installed pakets.
+fn user_arg() -> Arg {
+    Arg::new("user")
+        .long("user")
+        .help("Operate on the pakets of the current user in ~/.local instead of the system. Doesn't require root.")
+        .action(ArgAction::SetTrue)
+}
+
+/// `--root` option of the commands which operate on the installed pakets.
+fn root_arg() -> Arg {
+    Arg::new("root")
+        .long("root")
+        .help("Root folder of the system to operate on. Paket database is in <root>/var/lib/paket")
+        .value_name("folder")
+        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+        .conflicts_with("user")
+}
+
+/// `--repository` option of the commands which use the repositories, with the options to verify their indexes.
+fn repository_args() -> [Arg; 3] {
+    [
+        Arg::new("repository")
+            .long("repository")
+            .short('r')
+            .help("Repository served by paket-server or a repository folder, instead of the repositories in /etc/paket/repos.d. Can be given multiple times, the first repository which has a paket is used.\n- Example: `--repository http://localhost:8080` or `--repository file:///srv/repository`")
+            .value_name("url")
+            .value_parser(clap::builder::NonEmptyStringValueParser::new())
+            .action(ArgAction::Append),
+        Arg::new("trusted-key")
+            .long("trusted-key")
+            .help("Public key to verify the index of the --repository at the same position with. Printed by `paket-server keygen`")
+            .value_name("key")
+            .value_parser(clap::builder::NonEmptyStringValueParser::new())
+            .action(ArgAction::Append)
+            .requires("repository"),
+        Arg::new("allow-unsigned")
+            .long("allow-unsigned")
+            .help("Use the --repository indexes which have no --trusted-key without verifying them")
+            .action(ArgAction::SetTrue)
+            .requires("repository"),
+    ]
+}
+
+/// Options of the global configuration file which can be given to every command.
+fn config_args() -> [Arg; 5] {
+    [
+        Arg::new("config")
+            .long("config")
+            .help("Global configuration file to use instead of /etc/paket/paket.toml or $PAKET_CONFIG")
+            .value_name("file")
+            .value_parser(clap::builder::NonEmptyStringValueParser::new())
+            .global(true),
+        Arg::new("color")
+            .long("color")
+            .help("When the output is colored")
+            .value_parser(["auto", "always", "never"])
+            .global(true),
+        Arg::new("cache-dir")
+            .long("cache-dir")
+            .help("Folder to download the .paket files into")
+            .value_name("folder")
+            .value_parser(clap::builder::NonEmptyStringValueParser::new())
+            .global(true),
+        Arg::new("parallel-downloads")
+            .long("parallel-downloads")
+            .help("How many .paket files are downloaded at the same time")
+            .value_name("count")
+            .value_parser(clap::value_parser!(usize))
+            .global(true),
+        Arg::new("language")
+            .long("language")
+            .help("LANG of the maintainer scripts and the triggers. Example: `--language tr_TR.UTF-8`")
+            .value_name("lang")
+            .value_parser(clap::builder::NonEmptyStringValueParser::new())
+            .global(true),
+    ]
+}
 
 pub fn cli() -> Command {
     Command::new("paket")
@@ -6,6 +85,7 @@ pub fn cli() -> Command {
         .about("Blazing fast, memory safe and modern Linux package manager written in Rust.\nSource Repository: github.com/pardus-topluluk/paket")
         .subcommand_required(true)
         .arg_required_else_help(true)
+        .args(config_args())
         .subcommand(
             Command::new("build")
                 .alias("derle") // Turkish alias
@@ -14,6 +94,15 @@ pub fn cli() -> Command {
                     Arg::new("toml_path")
                         .default_value("./")
                         .help("Path to where Paket.toml located."),
+                )
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("Folder to write the .paket file into")
+                        .value_name("folder")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .default_value("./"),
                 ),
         )
         .subcommand(
@@ -27,7 +116,35 @@ pub fn cli() -> Command {
                         .value_parser(clap::builder::NonEmptyStringValueParser::new())
                         .action(ArgAction::Append)
                         .required(true),
-                ),
+                )
+                .arg(
+                    Arg::new("home-policy")
+                        .long("home-policy")
+                        .help("Home folders to install $USER_HOME, $XDG_CONFIG_HOME and $XDG_DATA_HOME files of configuration pakets")
+                        .value_parser(["invoking-user", "all-users", "skel"])
+                        .default_value("invoking-user"),
+                )
+                .arg(
+                    Arg::new("as-dependency")
+                        .long("as-dependency")
+                        .help("Mark the pakets as dependencies of other pakets instead of explicitly installed pakets")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("force-overwrite")
+                        .long("force-overwrite")
+                        .help("Overwrite the files owned by other installed pakets. They are owned by the new paket after the installation.")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("no-recommends")
+                        .long("no-recommends")
+                        .help("Don't install the recommended pakets of the downloaded pakets")
+                        .action(ArgAction::SetTrue),
+                )
+                .args(repository_args())
+                .arg(user_arg())
+                .arg(root_arg()),
         )
         .subcommand(
             Command::new("remove")
@@ -41,19 +158,300 @@ pub fn cli() -> Command {
                         .value_parser(clap::builder::NonEmptyStringValueParser::new())
                         .action(ArgAction::Append)
                         .required(true),
+                )
+                .arg(
+                    Arg::new("purge")
+                        .long("purge")
+                        .help("Remove the configuration files too. They are kept by default.")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("verify")
+                .alias("dogrula") // Turkish alias
+                .about("Check installed files of the pakets are missing or changed: content, mode and ownership.\nExit codes: 0 = no problems, 1 = changed or missing files, 2 = error\n- Example usage: `paket verify vlc`")
+                .arg(
+                    Arg::new("packages")
+                        .help("Package names to verify. All installed pakets are verified if not given.")
+                        .value_name("package-name")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .action(ArgAction::Append),
+                )
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("owns")
+                .about("Find the paket which installed a file.\n- Example usage: `paket owns /usr/bin/vlc` or `paket owns '/usr/share/icons/**/*.svg'`")
+                .arg(
+                    Arg::new("path")
+                        .help("Path of an installed file or a glob pattern. `*` doesn't match `/`, `**` matches folders.")
+                        .value_name("path")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .required(true),
+                )
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("bootstrap")
+                .about("Create a new root filesystem in an empty folder from .paket files and their dependencies.\n- Example usage: `paket bootstrap --root ./rootfs base_1.0.0.paket bash_5.2.0.paket`")
+                .arg(
+                    Arg::new("root")
+                        .long("root")
+                        .help("Empty folder to create the root filesystem in")
+                        .value_name("folder")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("pakets")
+                        .help(".paket files to install. Dependencies of every paket must be in the list.")
+                        .value_name("package_1.0.0.paket")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .action(ArgAction::Append)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            Command::new("export-oci")
+                .about("Export a root filesystem or .paket files as an OCI image layout tarball. No container runtime is needed.\n- Example usage: `paket export-oci --root ./rootfs -o image.tar`")
+                .arg(
+                    Arg::new("root")
+                        .long("root")
+                        .help("Root filesystem to export")
+                        .value_name("folder")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .required_unless_present("pakets")
+                        .conflicts_with("pakets"),
+                )
+                .arg(
+                    Arg::new("pakets")
+                        .help(".paket files to bootstrap into a temporary root filesystem and export")
+                        .value_name("package_1.0.0.paket")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help("Path of the image tarball to create")
+                        .value_name("image.tar")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("squash")
+                        .long("squash")
+                        .help("Create only one layer instead of a layer per paket")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("config-paket")
+                        .long("config-paket")
+                        .help("Installed paket to take the image labels and entrypoint from. Defaults to the last installed paket in the dependency order.")
+                        .value_name("package-name")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new()),
+                )
+                .arg(
+                    Arg::new("tag")
+                        .long("tag")
+                        .help("Reference name of the image. Defaults to the version of the config paket.")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new()),
+                ),
+        )
+        .subcommand(
+            Command::new("list")
+                .alias("listele") // Turkish alias
+                .about("List the installed pakets with their versions, types and sizes.\n- Example usage: `paket list --type library 'libgtk*'`")
+                .arg(
+                    Arg::new("pattern")
+                        .help("Only the pakets whose names match. Glob patterns like `lib*` match the whole name, other texts match a part of the name.")
+                        .value_name("name-pattern")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new()),
+                )
+                .arg(
+                    Arg::new("type")
+                        .long("type")
+                        .help("Only the pakets with this type")
+                        .value_parser(PackageType::all().iter().map(|t| t.name()).collect::<Vec<&str>>()),
+                )
+                .arg(
+                    Arg::new("explicit")
+                        .long("explicit")
+                        .help("Only the pakets installed explicitly")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("dependency"),
+                )
+                .arg(
+                    Arg::new("dependency")
+                        .long("dependency")
+                        .help("Only the pakets installed as dependencies of other pakets")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("upgradable")
+                        .long("upgradable")
+                        .help("Only the pakets which have a higher version in the repositories")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("info")
+                .alias("bilgi") // Turkish alias
+                .about("Show the information of an installed paket, a paket in the repositories or a .paket file.\n- Example usage: `paket info vlc` or `paket info vlc_3.0.20.paket`")
+                .arg(
+                    Arg::new("package")
+                        .help("Package name or .paket file name")
+                        .value_name("package-name or package_1.0.0.paket")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .required(true),
+                )
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("contents")
+                .alias("icerik") // Turkish alias
+                .about("List the files in a .paket file with their sizes, modes and the paths they will be installed to.\n- Example usage: `paket contents vlc_3.0.20.paket`")
+                .arg(
+                    Arg::new("paket")
+                        .help(".paket file to list")
+                        .value_name("package_1.0.0.paket")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .required(true),
+                )
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("extract")
+                .alias("cikar") // Turkish alias
+                .about("Extract the files in a .paket file into a folder without installing it.\n- Example usage: `paket extract vlc_3.0.20.paket -C ./vlc`")
+                .arg(
+                    Arg::new("paket")
+                        .help(".paket file to extract")
+                        .value_name("package_1.0.0.paket")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("directory")
+                        .short('C')
+                        .long("directory")
+                        .help("Folder to extract the files into")
+                        .value_name("folder")
+                        .value_parser(clap::builder::NonEmptyStringValueParser::new())
+                        .default_value("./"),
+                ),
+        )
+        .subcommand(
+            Command::new("update")
+                .alias("guncelle") // Turkish alias
+                .about("Download the indexes of the repositories in /etc/paket/repos.d if they are changed.\nIndexes are stored in /var/lib/paket/repos. If a new index is invalid or its signature doesn't match the trusted key, the last downloaded index is kept.\n- Example usage: `paket update`")
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("upgrade")
+                .alias("yukselt") // Turkish alias
+                .about("Upgrade all installed pakets to their newest versions in the repositories, with their new dependencies.\nRun `paket update` first to download the latest indexes.\n- Example usage: `paket upgrade`")
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Only show the pakets to upgrade and install, don't download them")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("no-recommends")
+                        .long("no-recommends")
+                        .help("Don't install the new recommended pakets of the upgraded pakets")
+                        .action(ArgAction::SetTrue),
+                )
+                .args(repository_args())
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("outdated")
+                .alias("eskiler") // Turkish alias
+                .about("List the installed pakets which have newer versions in the repositories. Nothing is downloaded or installed.\n- Example usage: `paket outdated` or `paket outdated --json`")
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print a JSON array of {name, installed_version, available_version, repository, url} objects")
+                        .action(ArgAction::SetTrue),
+                )
+                .args(repository_args())
+                .arg(user_arg())
+                .arg(root_arg()),
+        )
+        .subcommand(
+            Command::new("cache")
+                .alias("onbellek") // Turkish alias
+                .about("Manage the downloaded .paket files. They are kept by their names, versions and hashes, so reinstalls don't download them again.")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(
+                    Command::new("list")
+                        .about("List the downloaded .paket files.\n- Example usage: `paket cache list`")
+                        .arg(user_arg())
+                        .arg(root_arg()),
+                )
+                .subcommand(
+                    Command::new("clean")
+                        .about("Remove the downloaded .paket files. All of them are removed without options.\n- Example usage: `paket cache clean --keep 2` or `paket cache clean --max-size 500M`")
+                        .arg(
+                            Arg::new("keep")
+                                .long("keep")
+                                .help("Keep the newest N versions of every paket")
+                                .value_name("N")
+                                .value_parser(clap::value_parser!(usize)),
+                        )
+                        .arg(
+                            Arg::new("max-size")
+                                .long("max-size")
+                                .help("Remove the oldest downloads until the cache is smaller than this size. Example: `500M` or `2G`")
+                                .value_name("size")
+                                .value_parser(|s: &str| {
+                                    libpaket::cache::parse_size(s).ok_or("expected a size like 500M or 2G")
+                                }),
+                        )
+                        .arg(user_arg())
+                        .arg(root_arg()),
                 ),
         )
         .subcommand(
             Command::new("search")
                 .alias("ara") // Turkish alias
-                .about("Search a package\n- Example usage: `paket search vlc`")
+                .about("Search the pakets in the repositories and the installed pakets by their names, keywords, categories and descriptions.\nAll the words must match. Best matches are listed first.\n- Example usage: `paket search video player`")
                 .arg(
                     Arg::new("keywords")
-                        .help("Keywords to search on package name and descriptions")
+                        .help("Words to search. Case insensitive, `I` and `ı` match `i` too.")
                         .value_parser(clap::builder::NonEmptyStringValueParser::new())
                         .action(ArgAction::Append)
                         .required(true),
-                ),
+                )
+                .arg(
+                    Arg::new("installed")
+                        .long("installed")
+                        .help("Only the installed pakets")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("type")
+                        .long("type")
+                        .help("Only the pakets with this type")
+                        .value_parser(PackageType::all().iter().map(|t| t.name()).collect::<Vec<&str>>()),
+                )
+                .args(repository_args())
+                .arg(user_arg())
+                .arg(root_arg()),
         )
 }
 
@@ -66,3 +464,53 @@ pub fn err(e: &PaketError) {
 pub fn success(s: impl AsRef<str>) {
     println!("✅ {GREEN}{BOLD}[Success]:{RESET} {}", s.as_ref());
 }
+
+pub fn failure(s: impl AsRef<str>) {
+    eprintln!("❌ {RED}{BOLD}[Error]:{RESET} {}", s.as_ref());
+}
+
+pub fn warning(s: impl AsRef<str>) {
+    println!("⚠️ {YELLOW}{BOLD}[Warning]:{RESET} {}", s.as_ref());
+}
+
+/// Human readable size like `1.5 MiB`.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{size:.1} {}", UNITS[unit]),
+    }
+}
+
+/// Date and time of a unix time in UTC like `2024-03-01 14:05:09 UTC`.
+pub fn format_unix_time(seconds: u64) -> String {
+    let days = (seconds / 86400) as i64;
+    let time = seconds % 86400;
+
+    // Civil date from the days since 1970-01-01: https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
@@ -1,24 +1,240 @@
 use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 
-use libpaket::Result;
-use paket_cli::cli;
-use paket_cli::repo;
+use clap::ArgMatches;
+use libpaket::archive::{EntryKind, PaketArchive};
+use libpaket::cache::{self, CleanOptions};
+use libpaket::color::{self, *};
+use libpaket::config;
+use libpaket::install::{InstallOptions, Installer};
+use libpaket::oci::{LayerMode, OciExportOptions};
+use libpaket::placeholder::UserHomePolicy;
+use libpaket::query::ListFilter;
+use libpaket::remote::{self, RemotePaket};
+use libpaket::repos::{self, UpdateStatus};
+use libpaket::search::{search_pakets, SearchOptions};
+use libpaket::toml_structs::global_toml::{ColorMode, GlobalConfig};
+use libpaket::toml_structs::paket_toml::{self, Config, PackageType};
+use libpaket::upgrade::{outdated_pakets, plan_upgrade};
+use libpaket::{PaketError, Result};
+use paket_cli::{cli, repo};
+
+/// Load the global configuration and override it with the global arguments like `--cache-dir`.
+///
+/// The file given with `--config` must exist, the others are optional.
+fn global_config(matches: &ArgMatches) -> Result<GlobalConfig> {
+    let mut config = match matches.get_one::<String>("config") {
+        Some(path) => {
+            config::with_env_overrides(paket_toml::read_toml_file(Path::new(path))?, |name| {
+                std::env::var(name).ok()
+            })?
+        }
+        None => config::load()?,
+    };
+
+    if let Some(color) = matches.get_one::<String>("color") {
+        config.color = ColorMode::from_name(color).unwrap_or_default();
+    }
+    if let Some(cache_dir) = matches.get_one::<String>("cache-dir") {
+        config.cache_dir = Some(PathBuf::from(cache_dir));
+    }
+    if let Some(parallel_downloads) = matches.get_one::<usize>("parallel-downloads") {
+        config.parallel_downloads = *parallel_downloads;
+    }
+    if let Some(language) = matches.get_one::<String>("language") {
+        config.language = Some(language.clone());
+    }
+
+    Ok(config)
+}
+
+/// Installation options with the language of the global configuration.
+fn install_options(config: &GlobalConfig) -> InstallOptions {
+    InstallOptions {
+        language: config.language.clone(),
+        ..Default::default()
+    }
+}
+
+/// Create the installer from `--root` and `--user` arguments. The root of the global configuration is used by default.
+fn installer(config: &GlobalConfig, sub_matches: &ArgMatches) -> Result<Installer> {
+    let installer = if sub_matches.get_flag("user") {
+        Installer::for_user()?
+    } else {
+        match sub_matches.get_one::<String>("root") {
+            Some(root) => Installer::new(root),
+            None => Installer::new(&config.root),
+        }
+    };
+
+    Ok(installer.with_options(install_options(config)))
+}
+
+/// Remove the oldest downloads if the cache folder is bigger than `max_cache_size` of the configuration.
+fn limit_cache_size(config: &GlobalConfig, installer: &Installer) {
+    let result = config::max_cache_size(config).and_then(|max_size| match max_size {
+        Some(max_size) => cache::clean_cache(
+            &config::cache_dir(config, installer),
+            &CleanOptions {
+                max_size: Some(max_size),
+                ..Default::default()
+            },
+        ),
+        None => Ok(Vec::new()),
+    });
+
+    if let Err(e) = result {
+        cli::warning(format!("Cache folder is not cleaned: {e}"));
+    }
+}
+
+/// Repositories from the `--repository`, `--trusted-key` and `--allow-unsigned` arguments.
+fn repository_args(sub_matches: &ArgMatches) -> repo::RepositoryArgs {
+    let values = |id: &str| {
+        sub_matches
+            .get_many(id)
+            .map(|r| r.cloned().collect())
+            .unwrap_or_default()
+    };
+
+    repo::RepositoryArgs {
+        urls: values("repository"),
+        trusted_keys: values("trusted-key"),
+        allow_unsigned: sub_matches.get_flag("allow-unsigned"),
+    }
+}
+
+/// Print the fields of a Paket.toml.
+fn print_config(config: &Config) {
+    let package = &config.package;
+    let optional_list = |list: &Option<Vec<String>>| match list {
+        Some(l) => l.join(", "),
+        None => String::from("-"),
+    };
+
+    println!("{BOLD}Name:{RESET} {}", package.name);
+    println!("{BOLD}Version:{RESET} {}", package.version);
+    println!("{BOLD}Type:{RESET} {}", package.package_type.name());
+    println!("{BOLD}Description:{RESET} {}", package.description.trim());
+    println!(
+        "{BOLD}Maintainers:{RESET} {}",
+        package.maintainers.join(", ")
+    );
+    println!("{BOLD}License:{RESET} {}", package.license);
+    println!(
+        "{BOLD}Architectures:{RESET} {}",
+        package.architectures.join(", ")
+    );
+    println!(
+        "{BOLD}Homepage:{RESET} {}",
+        package.homepage.as_deref().unwrap_or("-")
+    );
+    println!(
+        "{BOLD}Source Repository:{RESET} {}",
+        package.source_repository.as_deref().unwrap_or("-")
+    );
+    println!(
+        "{BOLD}Keywords:{RESET} {}",
+        optional_list(&package.keywords)
+    );
+    println!(
+        "{BOLD}Categories:{RESET} {}",
+        optional_list(&package.categories)
+    );
+
+    let dependency_tables = match &config.dependencies {
+        Some(d) => vec![
+            ("Application", &d.application),
+            ("Library", &d.library),
+            ("Development", &d.development),
+            ("Recommended", &d.recommended),
+        ],
+        None => Vec::new(),
+    };
+    for (kind, table) in dependency_tables {
+        if let Some(table) = table {
+            let dependencies: Vec<String> = table
+                .iter()
+                .map(|(name, version)| format!("{name} ({})", version.as_str().unwrap_or("?")))
+                .collect();
+
+            println!(
+                "{BOLD}{kind} Dependencies:{RESET} {}",
+                dependencies.join(", ")
+            );
+        }
+    }
+}
+
+fn print_remote_paket(paket: &RemotePaket) {
+    let entry = &paket.entry;
+    let list = |list: &[String]| match list.is_empty() {
+        true => String::from("-"),
+        false => list.join(", "),
+    };
+    let dependencies = |table: &std::collections::BTreeMap<String, String>| {
+        let dependencies: Vec<String> = table
+            .iter()
+            .map(|(name, version)| format!("{name} ({version})"))
+            .collect();
+        list(&dependencies)
+    };
+
+    println!("{BOLD}Name:{RESET} {}", entry.name);
+    println!("{BOLD}Version:{RESET} {}", entry.version);
+    println!("{BOLD}Type:{RESET} {}", entry.package_type.name());
+    println!("{BOLD}Description:{RESET} {}", entry.description.trim());
+    println!(
+        "{BOLD}Architectures:{RESET} {}",
+        entry.architectures.join(", ")
+    );
+    println!("{BOLD}Keywords:{RESET} {}", list(&entry.keywords));
+    println!("{BOLD}Categories:{RESET} {}", list(&entry.categories));
+    println!(
+        "{BOLD}Dependencies:{RESET} {}",
+        dependencies(&entry.dependencies)
+    );
+    println!(
+        "{BOLD}Recommends:{RESET} {}",
+        dependencies(&entry.recommends)
+    );
+    println!("{BOLD}Repository:{RESET} {}", paket.repository);
+    println!(
+        "{BOLD}Download Size:{RESET} {}",
+        cli::human_size(entry.size)
+    );
+    println!("{BOLD}Installed:{RESET} no");
+}
 
 fn main() -> Result<()> {
     let matches = cli::cli().get_matches();
 
+    let mut config = match global_config(&matches) {
+        Ok(c) => c,
+        Err(e) => {
+            cli::err(&e);
+            std::process::exit(1);
+        }
+    };
+    color::set_enabled(config::use_color(&config));
+
     match matches.subcommand() {
         Some(("build", sub_matches)) => {
             let toml_path = sub_matches
                 .get_one::<String>("toml_path")
                 .expect("Expecting a valid --toml-path.");
+            let output = sub_matches.get_one::<String>("output").unwrap();
 
-            match libpaket::build::create_paket_from_toml(Path::new(toml_path)) {
-                Ok((filename, _file)) => {
-                    cli::success(format!("Paket Successfully Created at: {}", filename));
+            match libpaket::build::create_paket_from_toml(Path::new(toml_path), Path::new(output)) {
+                Ok((paket_path, _file)) => {
+                    cli::success(format!(
+                        "Paket Successfully Created at: {}",
+                        paket_path.display()
+                    ));
                 }
                 Err(e) => {
                     cli::err(&e);
+                    std::process::exit(1);
                 }
             };
         }
@@ -28,44 +244,625 @@ fn main() -> Result<()> {
             let (paket_files, package_names): (Vec<String>, Vec<String>) =
                 args.into_iter().partition(|e| e.ends_with(".paket"));
 
-            let paket_files: Vec<PathBuf> = paket_files.into_iter().map(PathBuf::from).collect();
+            let mut paket_files: Vec<PathBuf> =
+                paket_files.into_iter().map(PathBuf::from).collect();
 
-            println!(
-                "Packages will be installed from repository: {:?}",
-                package_names
-            );
-            println!(".paket files will be installed: {:?}", paket_files);
-
-            // TODO: Download & Install paket files
-            //match repo::download(&package_names) {
-            //    Ok(s) => {
-            //        cli::success(format!(".paket files downloaded: {s:?}"));
-            //    }
-            //    Err(e) => {
-            //        cli::err(&e);
-            //    }
-            //};
+            let home_policy = match sub_matches
+                .get_one::<String>("home-policy")
+                .unwrap()
+                .as_str()
+            {
+                "all-users" => UserHomePolicy::AllUsers,
+                "skel" => UserHomePolicy::Skel,
+                _ => UserHomePolicy::InvokingUser,
+            };
+            let installer = installer(&config, sub_matches)?.with_options(InstallOptions {
+                home_policy,
+                force_overwrite: sub_matches.get_flag("force-overwrite"),
+                install_as_dependency: sub_matches.get_flag("as-dependency"),
+                ..install_options(&config)
+            });
+            if sub_matches.get_flag("no-recommends") {
+                config.install_recommends = false;
+            }
+
+            let mut dependency_files = Vec::new();
+            if !package_names.is_empty() {
+                let repositories =
+                    match repo::repositories(&installer, &repository_args(sub_matches)) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            cli::err(&e);
+                            std::process::exit(1);
+                        }
+                    };
+
+                if repositories.is_empty() {
+                    cli::failure(format!(
+                        "No repositories to download {package_names:?} from. Add a repository to /etc/paket/repos.d or use --repository <url>"
+                    ));
+                    std::process::exit(1);
+                }
+
+                match repo::download_pakets(&installer, &config, &repositories, &package_names) {
+                    Ok((downloaded, dependencies)) => {
+                        paket_files.extend(downloaded);
+                        dependency_files = dependencies;
+                    }
+                    Err(e) => {
+                        cli::err(&e);
+                        std::process::exit(1);
+                    }
+                }
+            }
 
             // Install paket files:
-            match libpaket::install::install_paket_files(&paket_files) {
+            match installer.install_paket_files_with_dependencies(&paket_files, &dependency_files) {
                 Ok(s) => {
                     cli::success(format!("Paket Installed: {s:?}"));
                 }
                 Err(e) => {
                     cli::err(&e);
+                    std::process::exit(1);
                 }
             };
+
+            if !package_names.is_empty() {
+                limit_cache_size(&config, &installer);
+            }
         }
         Some(("remove", sub_matches)) => {
             let packages: Vec<String> =
                 sub_matches.get_many("packages").unwrap().cloned().collect();
+            let installer = installer(&config, sub_matches)?.with_options(InstallOptions {
+                purge_config_files: sub_matches.get_flag("purge"),
+                ..install_options(&config)
+            });
 
-            println!("Kaldırılacak paketler: {:?}", packages);
+            match installer.remove_pakets(&packages) {
+                Ok(_) => {
+                    cli::success(format!("Paket Removed: {packages:?}"));
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
         }
+        Some(("verify", sub_matches)) => {
+            let packages: Vec<String> = sub_matches
+                .get_many("packages")
+                .map(|p| p.cloned().collect())
+                .unwrap_or_default();
+            let installer = installer(&config, sub_matches)?;
+
+            let reports = match libpaket::verify::verify_pakets(&installer, &packages) {
+                Ok(r) => r,
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(2);
+                }
+            };
+
+            for report in &reports {
+                for file in &report.files {
+                    for problem in &file.problems {
+                        let message = format!(
+                            "{} {}: {} {problem}",
+                            report.name, report.version, file.path
+                        );
+
+                        if file.is_failure() {
+                            cli::failure(message);
+                        } else {
+                            cli::warning(format!("{message} (configuration file)"));
+                        }
+                    }
+                }
+
+                if report.is_ok() {
+                    cli::success(format!(
+                        "{} {}: {} files verified",
+                        report.name, report.version, report.checked_files
+                    ));
+                }
+            }
+
+            if !reports.iter().all(|r| r.is_ok()) {
+                std::process::exit(1);
+            }
+        }
+        Some(("owns", sub_matches)) => {
+            let path = sub_matches.get_one::<String>("path").unwrap();
+            let installer = installer(&config, sub_matches)?;
+
+            match libpaket::owners::find_owners(&installer, path) {
+                Ok(owners) if owners.is_empty() => {
+                    cli::failure(format!("No paket owns {path}"));
+                    std::process::exit(1);
+                }
+                Ok(owners) => {
+                    for owner in owners {
+                        println!("{} -> {}", owner.path, owner.pakets.join(", "));
+                    }
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+        }
+        Some(("bootstrap", sub_matches)) => {
+            let root = sub_matches.get_one::<String>("root").unwrap();
+            let paket_files: Vec<PathBuf> = sub_matches
+                .get_many::<String>("pakets")
+                .unwrap()
+                .map(PathBuf::from)
+                .collect();
+
+            match libpaket::bootstrap::bootstrap(Path::new(root), &paket_files) {
+                Ok(_) => {
+                    cli::success(format!("Root filesystem created at: {root}"));
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+        }
+        Some(("export-oci", sub_matches)) => {
+            let output = Path::new(sub_matches.get_one::<String>("output").unwrap());
+            let options = OciExportOptions {
+                layer_mode: match sub_matches.get_flag("squash") {
+                    true => LayerMode::Squashed,
+                    false => LayerMode::PerPaket,
+                },
+                config_paket: sub_matches.get_one::<String>("config-paket").cloned(),
+                tag: sub_matches.get_one::<String>("tag").cloned(),
+            };
+
+            let result = match sub_matches.get_one::<String>("root") {
+                Some(root) => {
+                    libpaket::oci::export_oci_image(&Installer::new(root), output, &options)
+                }
+                None => {
+                    let paket_files: Vec<PathBuf> = sub_matches
+                        .get_many::<String>("pakets")
+                        .unwrap()
+                        .map(PathBuf::from)
+                        .collect();
+
+                    libpaket::oci::export_oci_image_from_pakets(&paket_files, output, &options)
+                }
+            };
+
+            match result {
+                Ok(_) => {
+                    cli::success(format!("OCI image created at: {}", output.display()));
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+        }
+        Some(("list", sub_matches)) => {
+            let installer = installer(&config, sub_matches)?;
+
+            let dependency = match (
+                sub_matches.get_flag("explicit"),
+                sub_matches.get_flag("dependency"),
+            ) {
+                (true, _) => Some(false),
+                (_, true) => Some(true),
+                _ => None,
+            };
+
+            let upgradable = match sub_matches.get_flag("upgradable") {
+                true => match repos::stored_repositories(&installer) {
+                    Ok(repositories) => Some(remote::available_versions(&repositories)),
+                    Err(e) => {
+                        cli::err(&e);
+                        std::process::exit(1);
+                    }
+                },
+                false => None,
+            };
+
+            let filter = ListFilter {
+                package_type: sub_matches
+                    .get_one::<String>("type")
+                    .and_then(|t| PackageType::from_name(t)),
+                name_pattern: sub_matches.get_one::<String>("pattern").cloned(),
+                dependency,
+                upgradable,
+            };
+
+            match libpaket::query::list_pakets(&installer, &filter) {
+                Ok(pakets) => {
+                    for paket in pakets {
+                        println!(
+                            "{:<32} {:<16} {:<24} {:>10}",
+                            paket.config.package.name,
+                            paket.config.package.version,
+                            paket.config.package.package_type.name(),
+                            cli::human_size(paket.size)
+                        );
+                    }
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+        }
+        Some(("info", sub_matches)) => {
+            let package = sub_matches.get_one::<String>("package").unwrap();
+
+            if package.ends_with(".paket") {
+                match PaketArchive::open(package) {
+                    Ok(archive) => {
+                        print_config(archive.get_config());
+                        println!(
+                            "{BOLD}Archive Size:{RESET} {}",
+                            cli::human_size(std::fs::metadata(package)?.len())
+                        );
+                    }
+                    Err(e) => {
+                        cli::err(&e);
+                        std::process::exit(1);
+                    }
+                };
+            } else {
+                let installer = installer(&config, sub_matches)?;
+
+                match libpaket::query::find_installed_paket(&installer, package) {
+                    Ok(paket) => {
+                        print_config(&paket.config);
+                        println!(
+                            "{BOLD}Installed As:{RESET} {}",
+                            match paket.info.dependency {
+                                true => "dependency",
+                                false => "explicit",
+                            }
+                        );
+                        println!(
+                            "{BOLD}Install Date:{RESET} {}",
+                            paket
+                                .info
+                                .install_time
+                                .map(cli::format_unix_time)
+                                .unwrap_or(String::from("-"))
+                        );
+                        println!(
+                            "{BOLD}Installed Size:{RESET} {}",
+                            cli::human_size(paket.size)
+                        );
+                        println!("{BOLD}Files:{RESET} {}", paket.info.files.len());
+                    }
+                    // Pakets which are not installed are shown from the repositories
+                    Err(PaketError::NotInstalled(_)) => {
+                        match libpaket::query::find_repository_paket(&installer, package) {
+                            Ok(paket) => print_remote_paket(&paket),
+                            Err(e) => {
+                                cli::err(&e);
+                                std::process::exit(1);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        cli::err(&e);
+                        std::process::exit(1);
+                    }
+                };
+            }
+        }
+        Some(("contents", sub_matches)) => {
+            let paket = sub_matches.get_one::<String>("paket").unwrap();
+            let installer = installer(&config, sub_matches)?;
+
+            match libpaket::contents::paket_contents(&installer, Path::new(paket)) {
+                Ok(entries) => {
+                    for entry in entries {
+                        let kind = match entry.kind {
+                            EntryKind::File => '-',
+                            EntryKind::Folder => 'd',
+                            EntryKind::Symlink => 'l',
+                        };
+                        let targets: Vec<String> = entry
+                            .targets
+                            .iter()
+                            .map(|t| t.to_string_lossy().to_string())
+                            .collect();
+                        let link = match &entry.link_name {
+                            Some(l) => format!(" -> {}", l.display()),
+                            None => String::new(),
+                        };
+
+                        println!(
+                            "{kind}{:04o} {:>10}  {}{link}  => {}",
+                            entry.mode,
+                            cli::human_size(entry.size),
+                            entry.path.display(),
+                            targets.join(", ")
+                        );
+                    }
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+        }
+        Some(("extract", sub_matches)) => {
+            let paket = sub_matches.get_one::<String>("paket").unwrap();
+            let directory = sub_matches.get_one::<String>("directory").unwrap();
+
+            match libpaket::contents::extract_paket(Path::new(paket), Path::new(directory)) {
+                Ok(_) => cli::success(format!("Files are extracted to: {directory}")),
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+        }
+        Some(("update", sub_matches)) => {
+            let installer = installer(&config, sub_matches)?;
+
+            let repositories = match repos::read_repositories(&installer) {
+                Ok(r) => r,
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            if repositories.is_empty() {
+                cli::warning("No repositories in /etc/paket/repos.d to update.");
+            }
+
+            let mut failed = false;
+            for repository in repositories.iter().filter(|r| r.config.enabled) {
+                match repos::update_repository(&installer, repository) {
+                    Ok(UpdateStatus::Updated) => {
+                        cli::success(format!("{} is updated", repository.name))
+                    }
+                    Ok(UpdateStatus::NotModified) => {
+                        println!("{} is up to date", repository.name)
+                    }
+                    Err(e) => {
+                        failed = true;
+                        cli::failure(format!(
+                            "{} is not updated, the last downloaded index is kept: {e}",
+                            repository.name
+                        ));
+                    }
+                }
+            }
+
+            if failed {
+                std::process::exit(1);
+            }
+        }
+        Some(("upgrade", sub_matches)) => {
+            let installer = installer(&config, sub_matches)?;
+            if sub_matches.get_flag("no-recommends") {
+                config.install_recommends = false;
+            }
+
+            let plan = repo::repositories(&installer, &repository_args(sub_matches)).and_then(
+                |repositories| plan_upgrade(&installer, &repositories, config.install_recommends),
+            );
+            let plan = match plan {
+                Ok(p) => p,
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            for held in &plan.held_back {
+                cli::warning(format!(
+                    "{} is kept at {}, {} is not accepted by {}",
+                    held.name, held.installed_version, held.available_version, held.required_by
+                ));
+            }
+
+            if plan.is_empty() {
+                if plan.held_back.is_empty() {
+                    cli::success("All pakets are up to date.");
+                }
+                return Ok(());
+            }
+
+            println!("{BOLD}Pakets to upgrade:{RESET}");
+            for upgrade in &plan.upgrades {
+                println!(
+                    "    {:<32} {} -> {GREEN}{}{RESET}",
+                    upgrade.paket.entry.name,
+                    upgrade.installed_version,
+                    upgrade.paket.entry.version
+                );
+            }
+            if !plan.new_pakets.is_empty() {
+                println!("{BOLD}New pakets to install:{RESET}");
+                for paket in &plan.new_pakets {
+                    println!(
+                        "    {:<32} {GREEN}{}{RESET}",
+                        paket.entry.name, paket.entry.version
+                    );
+                }
+            }
+
+            if sub_matches.get_flag("dry-run") {
+                return Ok(());
+            }
+
+            let pakets: Vec<RemotePaket> = plan.pakets().cloned().collect();
+            let paket_files = match repo::download(&installer, &config, &pakets) {
+                Ok(f) => f,
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            // Upgrades keep their installation reasons, new pakets are installed as dependencies
+            let (upgrade_files, new_files) = paket_files.split_at(plan.upgrades.len());
+            match installer.install_paket_files_with_dependencies(upgrade_files, new_files) {
+                Ok(_) => {
+                    cli::success(format!("{} pakets are upgraded.", plan.upgrades.len()));
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            limit_cache_size(&config, &installer);
+        }
+        Some(("outdated", sub_matches)) => {
+            let installer = installer(&config, sub_matches)?;
+
+            let outdated = repo::repositories(&installer, &repository_args(sub_matches))
+                .and_then(|repositories| outdated_pakets(&installer, &repositories));
+            let outdated = match outdated {
+                Ok(o) => o,
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            if sub_matches.get_flag("json") {
+                match serde_json::to_string_pretty(&outdated) {
+                    Ok(json) => println!("{json}"),
+                    Err(e) => {
+                        cli::err(&PaketError::JsonSerializeError(e.to_string()));
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                for paket in outdated {
+                    println!(
+                        "{:<32} {:<16} {GREEN}{:<16}{RESET} {}",
+                        paket.name,
+                        paket.installed_version,
+                        paket.available_version,
+                        paket.repository
+                    );
+                }
+            }
+        }
+        Some(("cache", sub_matches)) => match sub_matches.subcommand() {
+            Some(("list", sub_matches)) => {
+                let installer = installer(&config, sub_matches)?;
+
+                match cache::list_cache(&config::cache_dir(&config, &installer)) {
+                    Ok(pakets) => {
+                        for paket in &pakets {
+                            let downloaded = paket
+                                .modified
+                                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                                .map(|d| cli::format_unix_time(d.as_secs()))
+                                .unwrap_or_default();
+                            println!(
+                                "{:<32} {:<16} {:>10}  {downloaded}  {}",
+                                paket.name,
+                                paket.version,
+                                cli::human_size(paket.size),
+                                &paket.sha256[..paket.sha256.len().min(12)]
+                            );
+                        }
+
+                        let total_size = pakets.iter().map(|p| p.size).sum();
+                        println!(
+                            "{BOLD}{} pakets, {}{RESET}",
+                            pakets.len(),
+                            cli::human_size(total_size)
+                        );
+                    }
+                    Err(e) => {
+                        cli::err(&e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            Some(("clean", sub_matches)) => {
+                let installer = installer(&config, sub_matches)?;
+                let options = CleanOptions {
+                    keep_versions: sub_matches.get_one::<usize>("keep").copied(),
+                    max_size: sub_matches.get_one::<u64>("max-size").copied(),
+                };
+
+                match cache::clean_cache(&config::cache_dir(&config, &installer), &options) {
+                    Ok(removed) => {
+                        let removed_size = removed.iter().map(|p| p.size).sum();
+                        cli::success(format!(
+                            "{} pakets are removed from the cache, {} is freed.",
+                            removed.len(),
+                            cli::human_size(removed_size)
+                        ));
+                    }
+                    Err(e) => {
+                        cli::err(&e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            _ => (),
+        },
         Some(("search", sub_matches)) => {
             let keywords: Vec<String> =
                 sub_matches.get_many("keywords").unwrap().cloned().collect();
-            println!("Aranacak kelimeler: {:?}", keywords);
+            let installer = installer(&config, sub_matches)?;
+
+            let options = SearchOptions {
+                package_type: sub_matches
+                    .get_one::<String>("type")
+                    .and_then(|t| PackageType::from_name(t)),
+                installed_only: sub_matches.get_flag("installed"),
+            };
+
+            let result = match options.installed_only {
+                true => Ok(Vec::new()),
+                false => repo::repositories(&installer, &repository_args(sub_matches)),
+            }
+            .and_then(|repositories| {
+                if repositories.is_empty() && !options.installed_only {
+                    cli::warning(
+                        "No repositories are configured, only the installed pakets are searched.",
+                    );
+                }
+
+                search_pakets(&installer, &repositories, &keywords, &options)
+            });
+
+            match result {
+                Ok(pakets) => {
+                    for paket in pakets {
+                        let installed = match &paket.installed_version {
+                            Some(v) if *v == paket.version => format!(" {GREEN}[installed]{RESET}"),
+                            Some(v) => format!(" {YELLOW}[installed: {v}]{RESET}"),
+                            None => String::new(),
+                        };
+                        println!(
+                            "{BOLD}{}{RESET} {} ({}){installed}",
+                            paket.name,
+                            paket.version,
+                            paket.package_type.name()
+                        );
+                        println!(
+                            "    {}",
+                            paket.description.trim().lines().next().unwrap_or_default()
+                        );
+                    }
+                }
+                Err(e) => {
+                    cli::err(&e);
+                    std::process::exit(1);
+                }
+            }
         }
 
         _ => (),
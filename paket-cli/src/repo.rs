@@ -1,2 +1,93 @@
-// TODO: Download .paket files from repository with http/3
-fn download_packages(packages: &[&str]) {}
+use std::path::PathBuf;
+
+use libpaket::config;
+use libpaket::install::Installer;
+use libpaket::remote::{self, RemotePaket, RemoteRepository};
+use libpaket::repos;
+use libpaket::toml_structs::global_toml::GlobalConfig;
+use libpaket::Result;
+
+/// Repositories given with the `--repository` arguments instead of the repositories in /etc/paket/repos.d.
+#[derive(Debug, Default)]
+pub struct RepositoryArgs {
+    pub urls: Vec<String>,
+
+    /// Public keys of the repositories in `urls` at the same positions.
+    pub trusted_keys: Vec<String>,
+
+    /// Use the repositories without a trusted key.
+    pub allow_unsigned: bool,
+}
+
+/// Repositories to install and search the pakets.
+///
+/// The indexes of the repositories in `args` are downloaded and verified if given, otherwise the indexes of the
+/// repositories in /etc/paket/repos.d downloaded by `paket update` are used.
+pub fn repositories(installer: &Installer, args: &RepositoryArgs) -> Result<Vec<RemoteRepository>> {
+    if args.urls.is_empty() {
+        return repos::stored_repositories(installer);
+    }
+
+    args.urls
+        .iter()
+        .enumerate()
+        .map(|(i, url)| {
+            let trusted_key = args.trusted_keys.get(i).map(String::as_str);
+            RemoteRepository::open(url, trusted_key, args.allow_unsigned)
+        })
+        .collect()
+}
+
+/// Download the pakets by their names from the repositories, with their dependencies which are not installed.
+///
+/// Recommended pakets are downloaded too if `install_recommends` of the configuration is true.
+/// .paket files are downloaded into the cache folder of the configuration. See: `config::cache_dir`
+///
+/// Returns the .paket files of the given pakets and the .paket files of their dependencies.
+pub fn download_pakets(
+    installer: &Installer,
+    config: &GlobalConfig,
+    repositories: &[RemoteRepository],
+    paket_names: &[String],
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let pakets = remote::resolve_pakets(
+        repositories,
+        paket_names,
+        &installer.installed_versions()?,
+        config.install_recommends,
+    )?;
+
+    let paket_paths = download(installer, config, &pakets)?;
+
+    let mut paket_files = Vec::new();
+    let mut dependency_files = Vec::new();
+    for (paket, paket_path) in pakets.iter().zip(paket_paths) {
+        if paket_names.contains(&paket.entry.name) {
+            paket_files.push(paket_path);
+        } else {
+            dependency_files.push(paket_path);
+        }
+    }
+
+    Ok((paket_files, dependency_files))
+}
+
+/// Download the pakets into the cache folder of the configuration. See: `config::cache_dir`
+pub fn download(
+    installer: &Installer,
+    config: &GlobalConfig,
+    pakets: &[RemotePaket],
+) -> Result<Vec<PathBuf>> {
+    for paket in pakets {
+        println!(
+            "Downloading {} {}: {}",
+            paket.entry.name, paket.entry.version, paket.url
+        );
+    }
+
+    remote::download_pakets(
+        pakets,
+        &config::cache_dir(config, installer),
+        config.parallel_downloads,
+    )
+}
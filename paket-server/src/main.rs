@@ -1,3 +1,167 @@
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+
+use clap::{Arg, Command};
+use libpaket::{repository, signature, PaketError};
+use paket_server::server::RepositoryServer;
+
+fn cli() -> Command {
+    Command::new("paket-server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Repository file server of the .paket files.\nSource Repository: github.com/pardus-topluluk/paket")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("serve")
+                .about("Serve a repository folder over HTTP: the indexes with their signatures and the .paket files, also in the channel folders like stable/\n- Example usage: `paket-server serve ./repository --address 0.0.0.0:8080`")
+                .arg(
+                    Arg::new("repository")
+                        .help("Repository folder with index.toml.gz and pool/")
+                        .value_name("folder")
+                        .default_value("./"),
+                )
+                .arg(
+                    Arg::new("address")
+                        .long("address")
+                        .help("Address to listen on")
+                        .value_name("ip:port")
+                        .default_value("0.0.0.0:8080"),
+                ),
+        )
+        .subcommand(
+            Command::new("index")
+                .about("Create or update the index of the .paket files in a repository folder and its sub folders.\nOnly the new and changed .paket files are read.\n- Example usage: `paket-server index ./repository --key ~/.config/paket-server/repository.key`")
+                .arg(
+                    Arg::new("repository")
+                        .help("Repository folder to write index.toml.gz into")
+                        .value_name("folder")
+                        .default_value("./"),
+                )
+                .arg(
+                    Arg::new("key")
+                        .long("key")
+                        .help("Secret key file to sign the index. Created with `paket-server keygen`")
+                        .value_name("file"),
+                ),
+        )
+        .subcommand(
+            Command::new("keygen")
+                .about("Create a secret key to sign the repository index and print its public key for the clients.\nKeep the key out of the repository folder, it must not be published.\n- Example usage: `paket-server keygen --output ~/.config/paket-server/repository.key`")
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .short('o')
+                        .help("File to write the secret key into [default: $XDG_CONFIG_HOME/paket-server/repository.key]")
+                        .value_name("file"),
+                ),
+        )
+}
+
 fn main() {
-    println!("Hello, world!");
+    let matches = cli().get_matches();
+
+    match matches.subcommand() {
+        Some(("serve", sub_matches)) => {
+            let repository = sub_matches.get_one::<String>("repository").unwrap();
+            let address = sub_matches.get_one::<String>("address").unwrap();
+
+            match RepositoryServer::bind(repository, address) {
+                Ok(server) => {
+                    println!("Serving {repository} on http://{address}");
+                    server.run();
+                }
+                Err(e) => exit_with_error(e),
+            }
+        }
+        Some(("index", sub_matches)) => {
+            let repository = sub_matches.get_one::<String>("repository").unwrap();
+            let secret_key = match sub_matches.get_one::<String>("key") {
+                Some(key_file) => match fs::read_to_string(key_file) {
+                    Ok(key) => Some(key),
+                    Err(e) => exit_with_error(e.into()),
+                },
+                None => {
+                    println!("Warning: No --key is given, the index will not be signed.");
+                    None
+                }
+            };
+
+            match repository::update_index(Path::new(repository), secret_key.as_deref()) {
+                Ok(changes) => {
+                    for filename in &changes.added {
+                        println!("Added: {filename}");
+                    }
+                    for filename in &changes.updated {
+                        println!("Updated: {filename}");
+                    }
+                    for filename in &changes.removed {
+                        println!("Removed: {filename}");
+                    }
+                    println!(
+                        "Index is updated: {} added, {} updated, {} removed, {} unchanged",
+                        changes.added.len(),
+                        changes.updated.len(),
+                        changes.removed.len(),
+                        changes.unchanged
+                    );
+                }
+                Err(e) => exit_with_error(e),
+            }
+        }
+        Some(("keygen", sub_matches)) => {
+            let output = match sub_matches.get_one::<String>("output") {
+                Some(output) => PathBuf::from(output),
+                None => match default_key_path() {
+                    Some(path) => path,
+                    None => exit_with_error(PaketError::FileNotFound(String::from(
+                        "$XDG_CONFIG_HOME or $HOME, use --output",
+                    ))),
+                },
+            };
+
+            let result = signature::generate_key_pair().and_then(|(secret_key, public_key)| {
+                if let Some(parent) = output.parent() {
+                    fs::DirBuilder::new()
+                        .recursive(true)
+                        .mode(0o700)
+                        .create(parent)?;
+                }
+
+                fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .mode(0o600)
+                    .open(&output)?
+                    .write_all(secret_key.as_bytes())?;
+
+                Ok(public_key)
+            });
+
+            match result {
+                Ok(public_key) => {
+                    println!("Secret key is written to: {}", output.display());
+                    println!("Public key of the clients: {public_key}");
+                }
+                Err(e) => exit_with_error(e),
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Secret key is kept in the config folder of the user by default, out of the repository folder which is published.
+fn default_key_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+
+    Some(config_home.join("paket-server/repository.key"))
+}
+
+fn exit_with_error(e: PaketError) -> ! {
+    eprintln!("{e}");
+    std::process::exit(1);
 }
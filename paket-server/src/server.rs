@@ -0,0 +1,172 @@
+use std::fs::File;
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use libpaket::repository::{INDEX_FILE, SIGNATURE_FILE};
+use libpaket::{PaketError, Result};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// HTTP file server of a repository folder. See: `libpaket::repository`
+///
+/// Serves only the indexes, their signatures and the .paket files with GET and HEAD requests. Indexes of the channels
+/// are in their sub folders like `stable/index.toml.gz`. Other files in the folder like the secret key of the
+/// repository are never served, and neither are symlinks going out of it.
+///
+/// Example:
+/// ```rust,no_run
+/// use paket_server::server::RepositoryServer;
+///
+/// let server = RepositoryServer::bind("./repository", "0.0.0.0:8080").unwrap();
+/// server.run();
+/// ```
+pub struct RepositoryServer {
+    repository_dir: PathBuf,
+    server: Server,
+}
+
+impl RepositoryServer {
+    /// Listen on the address like `127.0.0.1:8080`. Port `0` chooses a free port.
+    pub fn bind(repository_dir: impl Into<PathBuf>, address: &str) -> Result<RepositoryServer> {
+        let repository_dir: PathBuf = repository_dir.into();
+        if !repository_dir.is_dir() {
+            return Err(PaketError::FileNotFound(
+                repository_dir.to_string_lossy().to_string(),
+            ));
+        }
+        // Requested files are compared with the real path of the repository
+        let repository_dir = repository_dir.canonicalize()?;
+
+        let server = Server::http(address).map_err(|e| PaketError::IOError(e.to_string()))?;
+
+        Ok(RepositoryServer {
+            repository_dir,
+            server,
+        })
+    }
+
+    /// Address the server listens on.
+    pub fn get_address(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Answer the requests until the process stops.
+    pub fn run(&self) {
+        for request in self.server.incoming_requests() {
+            let method = request.method().clone();
+            let url = request.url().to_string();
+
+            match self.respond(request) {
+                Ok(status) => println!("{method} {url} {status}"),
+                Err(e) => eprintln!("{method} {url} failed: {e}"),
+            }
+        }
+    }
+
+    /// Send the requested file. Returns the status code of the response.
+    fn respond(&self, request: Request) -> Result<u16> {
+        if !matches!(request.method(), Method::Get | Method::Head) {
+            request.respond(Response::empty(405))?;
+            return Ok(405);
+        }
+
+        let file = request_path(&self.repository_dir, request.url())
+            .and_then(|p| p.canonicalize().ok())
+            .filter(|p| p.starts_with(&self.repository_dir) && p.is_file())
+            .and_then(|p| File::open(&p).ok().map(|f| (p, f)));
+
+        let (path, file) = match file {
+            Some(f) => f,
+            None => {
+                request.respond(Response::empty(404))?;
+                return Ok(404);
+            }
+        };
+
+        // Clients download the files again only if they are changed. See: `libpaket::repos::update_repository`
+        let etag = file_etag(&file)?;
+        let is_not_modified = request
+            .headers()
+            .iter()
+            .any(|h| h.field.equiv("If-None-Match") && h.value.as_str() == etag);
+
+        if is_not_modified {
+            request.respond(Response::empty(304).with_header(header("ETag", &etag)))?;
+            return Ok(304);
+        }
+
+        let content_type = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => "application/toml",
+            Some("gz") => "application/gzip",
+            _ => "application/octet-stream",
+        };
+
+        request.respond(
+            Response::from_file(file)
+                .with_header(header("Content-Type", content_type))
+                .with_header(header("ETag", &etag)),
+        )?;
+
+        Ok(200)
+    }
+}
+
+/// `ETag` of a file from its size and modification time.
+fn file_etag(file: &File) -> Result<String> {
+    let metadata = file.metadata()?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    Ok(format!(
+        "\"{:x}-{:x}-{:x}\"",
+        metadata.len(),
+        modified.as_secs(),
+        modified.subsec_nanos()
+    ))
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).unwrap()
+}
+
+/// Path of the requested file in the repository folder.
+///
+/// Only `index.toml.gz`, `index.toml.gz.sig` and `*.paket` files are served, in the repository folder or its sub
+/// folders like the channels and their pools. Paths going outside of the repository are rejected.
+fn request_path(repository_dir: &Path, url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    let path = PathBuf::from(percent_decode(path.trim_start_matches('/'))?);
+
+    let is_valid = path.components().all(|c| matches!(c, Component::Normal(_)));
+    let is_served = path
+        .file_name()
+        .is_some_and(|f| f == INDEX_FILE || f == SIGNATURE_FILE)
+        || path.extension().is_some_and(|e| e == "paket");
+
+    match is_valid && is_served {
+        true => Some(repository_dir.join(path)),
+        false => None,
+    }
+}
+
+/// Decode `%XX` escapes of a URL path.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
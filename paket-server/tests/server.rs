@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::path::Path;
+
+    use libpaket::build::create_paket_from_toml;
+    use libpaket::install::Installer;
+    use libpaket::remote::{download_paket, resolve_pakets};
+    use libpaket::repos::*;
+    use libpaket::repository::{update_index, write_index};
+    use libpaket::signature::generate_key_pair;
+    use libpaket::toml_structs::index_toml::RepositoryIndex;
+    use libpaket::toml_structs::repos_toml::RepositoryConfig;
+    use paket_server::server::RepositoryServer;
+
+    /// Send a HTTP/1.0 request and return the status code with the headers and the body.
+    fn send(
+        address: SocketAddr,
+        method: &str,
+        path: &str,
+        headers: &str,
+    ) -> (u16, String, Vec<u8>) {
+        let mut stream = TcpStream::connect(address).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.0\r\nHost: localhost\r\n{headers}\r\n"
+        )
+        .unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+
+        let header_end = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8_lossy(&response[..header_end]).to_string();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+
+        (status, head, response[header_end + 4..].to_vec())
+    }
+
+    /// Send a HTTP/1.0 request and return the status code with the body.
+    fn request(address: SocketAddr, method: &str, path: &str) -> (u16, Vec<u8>) {
+        let (status, _head, body) = send(address, method, path, "");
+        (status, body)
+    }
+
+    #[test]
+    fn serve_repository_files() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        fs::create_dir_all(repository.path().join("pool"))?;
+        fs::write(repository.path().join("index.toml.gz"), "pakets = []\n")?;
+        fs::write(
+            repository.path().join("pool/helloworld_0.1.0.paket"),
+            "paket",
+        )?;
+
+        let server = RepositoryServer::bind(repository.path(), "127.0.0.1:0")?;
+        let address = server.get_address().unwrap();
+        std::thread::spawn(move || server.run());
+
+        assert_eq!(
+            (200, b"pakets = []\n".to_vec()),
+            request(address, "GET", "/index.toml.gz")
+        );
+        assert_eq!(
+            (200, b"paket".to_vec()),
+            request(address, "GET", "/pool/helloworld%5F0.1.0.paket")
+        );
+        assert_eq!(404, request(address, "GET", "/pool/missing.paket").0);
+        assert_eq!(404, request(address, "GET", "/../secret").0);
+        assert_eq!(404, request(address, "GET", "/pool").0);
+        assert_eq!(405, request(address, "POST", "/index.toml.gz").0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn serve_only_repository_files() -> Result<(), Box<dyn std::error::Error>> {
+        let outside = tempfile::tempdir()?;
+        fs::write(outside.path().join("secret"), "secret")?;
+
+        let parent = tempfile::tempdir()?;
+        let repository = parent.path().join("repository");
+        fs::create_dir_all(repository.join("pool"))?;
+        fs::write(repository.join("index.toml.gz"), "pakets = []\n")?;
+        fs::write(repository.join("repository.key"), "secret key")?;
+        fs::write(repository.join("pool/notes.txt"), "notes")?;
+        fs::write(parent.path().join("secret.paket"), "secret")?;
+        std::os::unix::fs::symlink(
+            outside.path().join("secret"),
+            repository.join("pool/link.paket"),
+        )?;
+        std::os::unix::fs::symlink(outside.path(), repository.join("pool/outside"))?;
+
+        let server = RepositoryServer::bind(&repository, "127.0.0.1:0")?;
+        let address = server.get_address().unwrap();
+        std::thread::spawn(move || server.run());
+
+        assert_eq!(200, request(address, "GET", "/index.toml.gz").0);
+        for path in [
+            "/repository.key",
+            "/pool/notes.txt",
+            "/../secret.paket",
+            "/pool/../../secret.paket",
+            "/pool/%2e%2e/%2e%2e/secret.paket",
+            "/pool/link.paket",
+            "/pool/outside/secret",
+        ] {
+            assert_eq!((path, 404), (path, request(address, "GET", path).0));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn answer_not_modified_files() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        fs::write(repository.path().join("index.toml.gz"), "pakets = []\n")?;
+
+        let server = RepositoryServer::bind(repository.path(), "127.0.0.1:0")?;
+        let address = server.get_address().unwrap();
+        std::thread::spawn(move || server.run());
+
+        let (status, head, _body) = send(address, "GET", "/index.toml.gz", "");
+        assert_eq!(200, status);
+        let etag = head
+            .lines()
+            .find_map(|l| l.strip_prefix("ETag: "))
+            .unwrap()
+            .to_string();
+
+        let if_none_match = format!("If-None-Match: {etag}\r\n");
+        let (status, _head, body) = send(address, "GET", "/index.toml.gz", &if_none_match);
+        assert_eq!((304, Vec::new()), (status, body));
+
+        let (status, _head, _body) = send(
+            address,
+            "GET",
+            "/index.toml.gz",
+            "If-None-Match: \"old\"\r\n",
+        );
+        assert_eq!(200, status);
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_repository_from_server() -> Result<(), Box<dyn std::error::Error>> {
+        let repository = tempfile::tempdir()?;
+        let (secret_key, public_key) = generate_key_pair()?;
+        write_index(
+            repository.path(),
+            &RepositoryIndex::default(),
+            Some(&secret_key),
+        )?;
+
+        let server = RepositoryServer::bind(repository.path(), "127.0.0.1:0")?;
+        let address = server.get_address().unwrap();
+        std::thread::spawn(move || server.run());
+
+        let root = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let configured = ConfiguredRepository {
+            name: String::from("main"),
+            config: RepositoryConfig {
+                trusted_key: Some(public_key),
+                ..RepositoryConfig::new(format!("http://{address}"))
+            },
+        };
+
+        assert_eq!(
+            UpdateStatus::Updated,
+            update_repository(&installer, &configured)?
+        );
+        let state =
+            fs::read_to_string(installer.get_state_dir().join("repos/main/Repository.toml"))?;
+        assert!(state.contains("etag"));
+
+        assert_eq!(
+            UpdateStatus::NotModified,
+            update_repository(&installer, &configured)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn update_channel_from_server() -> Result<(), Box<dyn std::error::Error>> {
+        // The paket is in a sub folder of the channel which is not the pool
+        let repository = tempfile::tempdir()?;
+        let channel = repository.path().join("stable");
+        let folder = channel.join("extra");
+        fs::create_dir_all(&folder)?;
+        create_paket_from_toml(
+            Path::new("../libpaket/example_pakets/application_paket"),
+            &folder,
+        )?;
+        let (secret_key, public_key) = generate_key_pair()?;
+        update_index(&channel, Some(&secret_key))?;
+
+        let server = RepositoryServer::bind(repository.path(), "127.0.0.1:0")?;
+        let address = server.get_address().unwrap();
+        std::thread::spawn(move || server.run());
+
+        let root = tempfile::tempdir()?;
+        let installer = Installer::new(root.path());
+        let config_dir = root.path().join(REPOS_CONFIG_FOLDER);
+        fs::create_dir_all(&config_dir)?;
+        fs::write(
+            config_dir.join("main.toml"),
+            format!(
+                "url = \"http://{address}\"\nchannel = \"stable\"\ntrusted_key = \"{public_key}\"\n"
+            ),
+        )?;
+        let configured = read_repositories(&installer)?.remove(0);
+
+        assert_eq!(
+            UpdateStatus::Updated,
+            update_repository(&installer, &configured)?
+        );
+
+        let pakets = resolve_pakets(
+            &stored_repositories(&installer)?,
+            &[String::from("helloworld")],
+            &HashMap::new(),
+            true,
+        )?;
+        assert_eq!(
+            format!("http://{address}/stable/extra/helloworld_0.1.0.paket"),
+            pakets[0].url
+        );
+
+        let paket_path = download_paket(&pakets[0], &root.path().join("cache"))?;
+        assert_eq!(
+            fs::read(folder.join("helloworld_0.1.0.paket"))?,
+            fs::read(paket_path)?
+        );
+
+        Ok(())
+    }
+}